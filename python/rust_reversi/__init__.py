@@ -0,0 +1,5 @@
+from .rust_reversi import *
+
+__doc__ = rust_reversi.__doc__
+if hasattr(rust_reversi, "__all__"):
+    __all__ = rust_reversi.__all__
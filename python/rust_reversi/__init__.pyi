@@ -0,0 +1,702 @@
+from typing import Any, Callable, Dict, List, Optional, Tuple
+
+class Turn:
+    BLACK: "Turn"
+    WHITE: "Turn"
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __int__(self) -> int: ...
+    def __str__(self) -> str: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> Tuple[Any, ...]: ...
+
+class Color:
+    EMPTY: "Color"
+    BLACK: "Color"
+    WHITE: "Color"
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __int__(self) -> int: ...
+    def __str__(self) -> str: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> Tuple[Any, ...]: ...
+
+class Board:
+    def __init__(self) -> None: ...
+    def get_board(self) -> Tuple[int, int, Turn]: ...
+    def get_turn(self) -> Turn: ...
+    def set_board(self, player_board: int, opponent_board: int, turn: Turn) -> None: ...
+    def set_board_str(self, line: str, turn: Turn) -> None: ...
+    def get_board_line(self) -> str: ...
+    def get_board_vec_black(self) -> List[Color]: ...
+    def get_board_vec_turn(self) -> List[Color]: ...
+    def get_board_matrix(self) -> List[List[List[int]]]: ...
+    def get_board_planes(self, history: int = 0) -> "numpy.ndarray": ...
+    """Board as float32 planes of shape (3 + 2 * history, 8, 8).
+    Requires the `numpy` feature.
+    Planes:
+        0: player's discs, 1: opponent's discs, 2: legal moves,
+        then player's and opponent's discs of each of the last `history` positions,
+        seen from the side to move. Missing history planes are zero.
+    """
+    @staticmethod
+    def stack_board_planes(boards: List["Board"], history: int = 0) -> "numpy.ndarray": ...
+    """get_board_planes of many boards stacked into shape (N, C, 8, 8).
+    Requires the `numpy` feature.
+    """
+    def get_legal_moves_array(self) -> "numpy.ndarray": ...
+    """Legal moves as a bool array of shape (64,). Requires the `numpy` feature."""
+    def player_piece_num(self) -> int: ...
+    def opponent_piece_num(self) -> int: ...
+    def black_piece_num(self) -> int: ...
+    def white_piece_num(self) -> int: ...
+    def piece_sum(self) -> int: ...
+    def diff_piece_num(self) -> int: ...
+    def get_legal_moves(self) -> int: ...
+    def get_legal_moves_vec(self) -> List[int]: ...
+    def get_legal_moves_tf(self) -> List[bool]: ...
+    def is_legal_move(self, pos: int) -> bool: ...
+    def get_child_boards(self) -> List["Board"]: ...
+    def get_stable_discs(self) -> Tuple[int, int]: ...
+    """Bitboards of discs that can never be flipped, as (player, opponent).
+    Conservative: every reported disc is stable, but a few may be missed.
+    """
+    def get_edge_stable_discs(self) -> Tuple[int, int]: ...
+    """Stable discs on the edges, as (player, opponent)"""
+    def get_frontier_discs(self) -> Tuple[int, int]: ...
+    """Discs adjacent to an empty square, as (player, opponent)"""
+    def get_potential_mobility(self) -> Tuple[int, int]: ...
+    """Empty squares adjacent to the other side's discs, as (player, opponent)"""
+    def get_corner_discs(self) -> Tuple[int, int]: ...
+    def get_x_square_discs(self) -> Tuple[int, int]: ...
+    def get_c_square_discs(self) -> Tuple[int, int]: ...
+    def get_empty_regions(self) -> List[int]: ...
+    """Bitboards of the 8-connected regions of empty squares"""
+    def get_odd_empty_regions(self) -> int: ...
+    """Bitboard of the empty squares in odd-sized regions"""
+    def get_flips(self, pos: int) -> int: ...
+    """Bitboard of the discs flipped by playing at pos"""
+    def get_flips_vec(self, pos: int) -> List[int]: ...
+    def peek_move(self, pos: int) -> "Board": ...
+    """Copy of the board with the move applied. The board itself is unchanged."""
+    def do_move(self, pos: int) -> None: ...
+    def do_pass(self) -> None: ...
+    def undo(self) -> None: ...
+    def redo(self) -> None: ...
+    def can_undo(self) -> bool: ...
+    def can_redo(self) -> bool: ...
+    def history(self) -> List[Optional[int]]: ...
+    """Moves played so far, oldest first. Passes are recorded as None."""
+    def move_count(self) -> int: ...
+    @staticmethod
+    def pos_to_str(pos: int) -> str: ...
+    """Convert a position index to a move string such as "f5" """
+    @staticmethod
+    def str_to_pos(s: str) -> int: ...
+    """Convert a move string such as "f5" to a position index"""
+    def do_move_str(self, s: str) -> None: ...
+    @staticmethod
+    def from_transcript(transcript: str) -> "Board": ...
+    """Replay a transcript such as "f5d6c3" from the initial position.
+    Passes are inserted automatically. Raises ValueError on an illegal move.
+    """
+    def to_transcript(self) -> str: ...
+    """Transcript of the recorded moves, passes omitted"""
+    def is_pass(self) -> bool: ...
+    def is_game_over(self) -> bool: ...
+    def is_win(self) -> bool: ...
+    def is_lose(self) -> bool: ...
+    def is_draw(self) -> bool: ...
+    def is_black_win(self) -> bool: ...
+    def is_white_win(self) -> bool: ...
+    def get_winner(self) -> Optional[Turn]: ...
+    def get_random_move(self, seed: Optional[int] = None) -> int: ...
+    """Random legal move.
+    Args:
+        seed: Seed for this call only. If None, the board RNG set by set_rng_seed is used,
+            or the thread RNG if none was set.
+    """
+    def set_rng_seed(self, seed: Optional[int] = None) -> None: ...
+    """Seed the RNG used by get_random_move. None goes back to the thread RNG."""
+    def __str__(self) -> str: ...
+    def clone(self) -> "Board": ...
+    def rotate90(self) -> "Board": ...
+    """Board rotated 90 degrees clockwise. Same as transform(1)."""
+    def flip_horizontal(self) -> "Board": ...
+    """Board mirrored left-right. Same as transform(4)."""
+    def flip_vertical(self) -> "Board": ...
+    """Board mirrored top-bottom. Same as transform(5)."""
+    def flip_diagonal(self) -> "Board": ...
+    """Board mirrored along the a1-h8 diagonal. Same as transform(6)."""
+    def transform(self, transform: int) -> "Board": ...
+    """Apply one of the 8 symmetries. History is not carried over.
+    Args:
+        transform: 0: identity, 1: rotate 90, 2: rotate 180, 3: rotate 270,
+            4: flip horizontal, 5: flip vertical, 6: flip diagonal, 7: flip anti-diagonal
+    """
+    def symmetries(self) -> List["Board"]: ...
+    """All 8 symmetric boards, indexed by transform"""
+    def canonical(self) -> Tuple["Board", int]: ...
+    """Symmetric board with the smallest (player_board, opponent_board), and its transform"""
+    @staticmethod
+    def transform_pos(pos: int, transform: int) -> int: ...
+    """Map a position index through the same transform as `transform`"""
+    @staticmethod
+    def inverse_transform(transform: int) -> int: ...
+    """Transform that undoes `transform`"""
+    def hash(self) -> int: ...
+    """Zobrist hash of the position. Stable across processes and platforms."""
+    def __hash__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    """Positions are equal if discs and side to move match. History is ignored."""
+    def __copy__(self) -> "Board": ...
+    def __deepcopy__(self, memo: Any) -> "Board": ...
+    def __getstate__(self) -> Tuple[Any, ...]: ...
+    def __setstate__(self, state: Tuple[Any, ...]) -> None: ...
+    def __reduce__(self) -> Tuple[Any, ...]: ...
+
+class BoardBatch:
+    """N boards stepped together in Rust. Action 64 is a pass."""
+
+    def __init__(self, n: int, auto_pass: bool = True) -> None: ...
+    """Initialize BoardBatch
+    Args:
+        n: Number of boards
+        auto_pass: Pass automatically after a step when the side to move has no legal move
+    """
+    def __len__(self) -> int: ...
+    def step(self, actions: List[int]) -> None: ...
+    """Apply one action per board. Actions on finished boards are ignored.
+    Raises ValueError without changing any board if an action is illegal.
+    """
+    def legal_moves_mask(self) -> List[List[bool]]: ...
+    """Legal actions of each board, shape (N, 65). Index 64 is a pass."""
+    def dones(self) -> List[bool]: ...
+    def rewards(self, turn: Optional[Turn] = None) -> List[float]: ...
+    """1.0 for a win, -1.0 for a loss and 0.0 for a draw or an unfinished game,
+    seen from `turn` (default: Turn.BLACK)
+    """
+    def turns(self) -> List[Turn]: ...
+    def reset(self, indices: Optional[List[int]] = None) -> None: ...
+    """Reset the given boards, or all boards, to the initial position"""
+    def get_board(self, index: int) -> Board: ...
+    def get_boards(self) -> List[Board]: ...
+    def set_board(self, index: int, board: Board) -> None: ...
+
+class OthelloEnv:
+    """Othello environment played against a fixed opponent.
+
+    reset and step have the signatures of Gymnasium. rust_reversi.gym.OthelloGymEnv
+    wraps it as a gymnasium.Env. Observations are float32 arrays of shape (3, 8, 8):
+    player's discs, opponent's discs and legal moves, seen from the agent. Built without
+    the numpy feature, they are nested lists of the same shape. Actions are 0-63 for a
+    move and 64 for a pass. `gymnasium` is required for the space attributes, which are
+    built on first access.
+    """
+
+    metadata: Dict[str, Any]
+    render_mode: str
+    action_space: Any
+    """gymnasium.spaces.Discrete(65)"""
+    observation_space: Any
+    """gymnasium.spaces.Box(0.0, 1.0, (3, 8, 8), float32)"""
+    unwrapped: "OthelloEnv"
+    board: Board
+
+    def __init__(
+        self,
+        opponent: Optional[Any] = None,
+        agent_turn: Optional[Turn] = Turn.BLACK,
+        reward: str = "win_loss",
+        seed: Optional[int] = None,
+    ) -> None: ...
+    """Initialize OthelloEnv
+    Args:
+        opponent: Object with `get_move(board)` such as AlphaBetaSearch, ThunderSearch or
+            MctsSearch. None plays uniformly random moves.
+        agent_turn: Color of the agent. None picks a random color on every reset.
+        reward: "win_loss" for +1/-1/0 at the end of the game,
+            "disc_diff" for the final disc difference
+        seed: Seed for random opponent moves and random colors
+    """
+    def reset(
+        self, *, seed: Optional[int] = None, options: Optional[Dict[str, Any]] = None
+    ) -> Tuple[Any, Dict[str, Any]]: ...
+    def step(self, action: int) -> Tuple[Any, float, bool, bool, Dict[str, Any]]: ...
+    """Play the agent's action, then the opponent's replies.
+    Returns (observation, reward, terminated, truncated, info).
+    Raises ValueError on an illegal action.
+    """
+    def action_masks(self) -> List[bool]: ...
+    """Legal actions of length 65. Also available as info["action_mask"]."""
+    def render(self) -> str: ...
+    def close(self) -> None: ...
+
+class Arena:
+    def __init__(
+        self, command1: List[str], command2: List[str], show_progress: bool = True
+    ) -> None: ...
+    def play_n(self, n: int) -> None: ...
+    def get_stats(self) -> Tuple[int, int, int]: ...
+    def get_pieces(self) -> Tuple[int, int]: ...
+
+class NetworkArenaServer:
+    def __init__(self, game_per_iter: int, show_progress: bool = True) -> None: ...
+    def start(self, address: str, port: int) -> None: ...
+
+class NetworkArenaClient:
+    def __init__(self, command: List[str]) -> None: ...
+    def connect(self, address: str, port: int) -> None: ...
+    def get_stats(self) -> Tuple[int, int, int]: ...
+    def get_pieces(self) -> Tuple[int, int]: ...
+
+class SearchResult:
+    """Result of search() on AlphaBetaSearch, ThunderSearch or MctsSearch"""
+
+    best_move: Optional[int]
+    """Best move, or None if the player must pass or the game is over"""
+    score: float
+    """Score of the best move from the side to move's point of view"""
+    pv: List[Optional[int]]
+    """Expected line of play starting with best_move. None stands for a pass"""
+    depth: int
+    """Depth of the last completed iteration for AlphaBetaSearch,
+    deepest visited ply for ThunderSearch, MctsSearch and PuctSearch"""
+    nodes: int
+    """Positions visited for AlphaBetaSearch, playouts for ThunderSearch, MctsSearch and PuctSearch"""
+    elapsed_ms: float
+    timed_out: bool
+    """Whether the timeout, the node budget or stop() stopped the search early"""
+
+class SearchHandle:
+    """Search running in a background thread, returned by start_search()"""
+
+    def is_done(self) -> bool: ...
+    def stop(self) -> None: ...
+    """Stop the search. result() then returns the result so far"""
+    def result(self) -> SearchResult: ...
+    """Wait for the search to finish and return its result"""
+
+class MoveAnalysis:
+    """One root move reported by analyze()"""
+
+    move: int
+    score: float
+    """Score after playing the move from the side to move's point of view.
+    0.5 for MctsSearch moves that were never visited"""
+    pv: List[Optional[int]]
+    """Expected line of play starting with the move. None stands for a pass"""
+    visits: Optional[int]
+    """Visit count for MctsSearch, None for AlphaBetaSearch"""
+
+class SearchPolicy:
+    """Root statistics returned by get_policy(), indexed by square with the pass at 64"""
+
+    visits: List[int]
+    policy: List[float]
+    """Share of the root visits, summing to 1. All zeros if the game is over"""
+    winrates: List[float]
+    """Mean winrate after the move for the side to move. 0.5 for moves never visited"""
+    q_values: List[float]
+    """Winrates mapped to [-1, 1], so 0 for moves never visited"""
+    nodes: int
+    """Playouts run by this call"""
+
+class Evaluator:
+    def __init__(self) -> None: ...
+    def evaluate(self, board: Board) -> int: ...
+    def set_py_evaluator(self, evaluator: "Evaluator") -> None: ...
+    """Evaluate boards with evaluator.evaluate(board).
+    If evaluator also defines evaluate_batch(boards), AlphaBetaSearch calls it with
+    the children of each node one move above the leaves and expects one value per
+    board, as a list or a 1-d ndarray. Values are rounded to int.
+    An exception raised by it stops the search and is re-raised by the search method"""
+
+class PieceEvaluator(Evaluator):
+    def __init__(self) -> None: ...
+    def evaluate(self, board: Board) -> int: ...
+
+class LegalNumEvaluator(Evaluator):
+    def __init__(self) -> None: ...
+    def evaluate(self, board: Board) -> int: ...
+
+class MatrixEvaluator(Evaluator):
+    def __init__(self, matrix: List[List[int]]) -> None: ...
+    def evaluate(self, board: Board) -> int: ...
+
+class AlphaBetaSearch:
+    persist_tt: bool
+    def __init__(
+        self,
+        evaluator: Evaluator,
+        depth: int,
+        win_score: int,
+        tt_size_mb: int = 16,
+        persist_tt: bool = False,
+        threads: int = 1,
+    ) -> None: ...
+    """Initialize AlphaBetaSearch
+    Args:
+        evaluator: Evaluator
+        depth: Search depth
+        win_score: Score of a win. Must be greater than any evaluation
+        tt_size_mb: Transposition table size in megabytes, allocated by the first search.
+            0 disables the table
+        persist_tt: Keep table entries between calls, e.g. for the moves of one game
+        threads: Number of search threads sharing the transposition table.
+            Needs the transposition table
+    """
+    def get_move(self, board: Board) -> int: ...
+    def get_move_with_timeout(self, board: Board, timeout_ms: int) -> int: ...
+    def get_move_with_nodes(self, board: Board, max_nodes: int) -> int: ...
+    """Best move found by iterative deepening within max_nodes visited positions"""
+    def get_search_score(self, board: Board) -> float: ...
+    def search(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        callback: Optional[Callable[[SearchResult], None]] = None,
+        max_nodes: Optional[int] = None,
+    ) -> SearchResult: ...
+    """Search once
+    Args:
+        board: Board to search
+        timeout_ms: Timeout in milliseconds. None searches to the full depth
+        callback: Called with the result so far after each completed depth.
+            An exception raised by the callback stops the search and is re-raised
+        max_nodes: Number of positions to visit. Unlike timeout_ms the result
+            does not depend on the machine
+    """
+    def start_search(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        callback: Optional[Callable[[SearchResult], None]] = None,
+        max_nodes: Optional[int] = None,
+    ) -> SearchHandle: ...
+    """Search in a background thread. Arguments are the same as search()"""
+    def stop(self) -> None: ...
+    """Stop every running search of this instance. They return the result so far"""
+    def analyze(
+        self, board: Board, multi_pv: Optional[int] = None
+    ) -> List[MoveAnalysis]: ...
+    """Score and principal variation of each legal move, best first
+    Args:
+        board: Board to analyze
+        multi_pv: Number of moves to report. None reports every legal move
+    Returns:
+        Empty if the player must pass or the game is over
+    """
+    def clear_tt(self) -> None: ...
+    """Remove every transposition table entry"""
+
+class WinrateEvaluator:
+    def __init__(self) -> None: ...
+    def evaluate(self, board: Board) -> float: ...
+    def set_py_evaluator(self, evaluator: "WinrateEvaluator") -> None: ...
+    """Evaluate boards with evaluator.evaluate(board).
+    If evaluator also defines evaluate_batch(boards), ThunderSearch calls it with
+    batch_size leaves at a time and expects one winrate per board, as a list or a 1-d ndarray.
+    An exception raised by it stops the search and is re-raised by the search method"""
+
+class ThunderSearch:
+    def __init__(
+        self,
+        evaluator: WinrateEvaluator,
+        n_playout: int,
+        epsilon: float,
+        seed: Optional[int] = None,
+        batch_size: int = 16,
+        threads: int = 1,
+    ) -> None: ...
+    """Initialize ThunderSearch
+    Args:
+        evaluator: Winrate evaluator
+        n_playout: Number of playouts
+        epsilon: Exploration rate
+        seed: RNG seed. The same seed gives the same sequence of moves. None seeds from the OS
+        batch_size: Leaves evaluated per evaluate_batch call, selected with virtual losses.
+            Only used if the evaluator defines evaluate_batch
+        threads: Number of search threads. Each grows its own tree with its share
+            of the playouts and the trees are merged
+    Raises:
+        ValueError: If epsilon is not between 0 and 1
+    """
+    def get_move(self, board: Board) -> int: ...
+    def get_move_with_timeout(self, board: Board, timeout_ms: int) -> int: ...
+    def get_move_with_playouts(self, board: Board, n_playouts: int) -> int: ...
+    """Best move found with n_playouts playouts instead of the number given at construction"""
+    def get_search_score(self, board: Board) -> float: ...
+    def search(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        callback: Optional[Callable[[SearchResult], None]] = None,
+        callback_interval: int = 1000,
+        n_playouts: Optional[int] = None,
+    ) -> SearchResult: ...
+    """Search once
+    Args:
+        board: Board to search
+        timeout_ms: Timeout in milliseconds. None runs every playout
+        callback: Called with the result so far every callback_interval playouts.
+            An exception raised by the callback stops the search and is re-raised
+        callback_interval: Number of playouts between callbacks
+        n_playouts: Number of playouts for this call instead of the one given at construction
+    """
+    def start_search(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        callback: Optional[Callable[[SearchResult], None]] = None,
+        callback_interval: int = 1000,
+        n_playouts: Optional[int] = None,
+    ) -> SearchHandle: ...
+    """Search in a background thread. Arguments are the same as search()"""
+    def stop(self) -> None: ...
+    """Stop every running search of this instance. They return the result so far"""
+    def get_policy(self, board: Board, n_playouts: Optional[int] = None) -> SearchPolicy: ...
+    """Search once and return the visits and winrates of the root moves
+    Args:
+        board: Board to search
+        n_playouts: Number of playouts for this call instead of the one given at construction
+    """
+
+class RolloutPolicy:
+    """How MctsSearch plays out the leaves"""
+
+    @staticmethod
+    def random() -> "RolloutPolicy": ...
+    """Uniformly random moves (the default)"""
+    @staticmethod
+    def epsilon_greedy(evaluator: Evaluator, epsilon: float = 0.1) -> "RolloutPolicy": ...
+    """Best move for evaluator, or a random move with probability epsilon
+    Raises:
+        ValueError: If epsilon is not between 0 and 1
+    """
+    @staticmethod
+    def corner_first() -> "RolloutPolicy": ...
+    """A random corner if one is legal, otherwise a random move"""
+    def with_cutoff(self, plies: int, evaluator: WinrateEvaluator) -> "RolloutPolicy": ...
+    """Same policy stopping after plies plies and taking the winrate of evaluator
+    as the playout result"""
+
+class MctsSearch:
+    """Monte Carlo Tree Search Search"""
+
+    reuse_tree: bool
+    def __init__(
+        self,
+        n_playout: int,
+        c: float,
+        expand_threshold: int,
+        seed: Optional[int] = None,
+        reuse_tree: bool = False,
+        threads: int = 1,
+        rollout_policy: Optional[RolloutPolicy] = None,
+    ) -> None: ...
+    """Initialize MctsSearch
+    Args:
+        n_playout: Number of playouts
+        c: Exploration constant. c=1.0 is a good default value
+        expand_threshold: Threshold for expanding the tree. expand_threshold=10 is a good default value
+        seed: RNG seed. The same seed gives the same sequence of moves. None seeds from the OS
+        reuse_tree: Keep the tree of the last search and search it further if the next
+            board is reached from it within two plies (own move and opponent reply)
+        threads: Number of search threads. Each grows its own tree with its share
+            of the playouts and the trees are merged
+        rollout_policy: How leaves are played out. None plays uniformly random moves
+    """
+    def get_move(self, board: Board) -> int: ...
+    def get_move_with_timeout(self, board: Board, timeout_ms: int) -> int: ...
+    def get_move_with_playouts(self, board: Board, n_playouts: int) -> int: ...
+    """Best move found with n_playouts playouts instead of the number given at construction"""
+    def get_search_score(self, board: Board) -> float: ...
+    def search(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        callback: Optional[Callable[[SearchResult], None]] = None,
+        callback_interval: int = 1000,
+        n_playouts: Optional[int] = None,
+    ) -> SearchResult: ...
+    """Search once
+    Args:
+        board: Board to search
+        timeout_ms: Timeout in milliseconds. None runs every playout
+        callback: Called with the result so far every callback_interval playouts.
+            An exception raised by the callback stops the search and is re-raised
+        callback_interval: Number of playouts between callbacks
+        n_playouts: Number of playouts for this call instead of the one given at construction
+    """
+    def start_search(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        callback: Optional[Callable[[SearchResult], None]] = None,
+        callback_interval: int = 1000,
+        n_playouts: Optional[int] = None,
+    ) -> SearchHandle: ...
+    """Search in a background thread. Arguments are the same as search()"""
+    def stop(self) -> None: ...
+    """Stop every running search of this instance. They return the result so far"""
+    def clear_tree(self) -> None: ...
+    """Discard the kept tree"""
+    def get_policy(self, board: Board, n_playouts: Optional[int] = None) -> SearchPolicy: ...
+    """Search once and return the visits and winrates of the root moves
+    Args:
+        board: Board to search
+        n_playouts: Number of playouts for this call instead of the one given at construction
+    """
+    def analyze(
+        self, board: Board, multi_pv: Optional[int] = None
+    ) -> List[MoveAnalysis]: ...
+    """Visit count, winrate and principal variation of each legal move, most visited first
+    Args:
+        board: Board to analyze
+        multi_pv: Number of moves to report. None reports every legal move
+    Returns:
+        Empty if the player must pass or the game is over
+    """
+
+class PolicyValueEvaluator:
+    def __init__(self) -> None: ...
+    """Same prior for every legal move and the winrate of WinrateEvaluator()"""
+    def evaluate(self, board: Board) -> Tuple[List[float], float]: ...
+    """Priors indexed by square (64 or 65 values, the pass entry is ignored)
+    and winrate for the side to move"""
+    def set_py_evaluator(self, evaluator: "PolicyValueEvaluator") -> None: ...
+    """Evaluate boards with evaluator.evaluate(board).
+    If evaluator also defines evaluate_batch(boards) returning (policies, values),
+    PuctSearch calls it with batch_size leaves at a time.
+    An exception raised by it stops the search and is re-raised by the search method"""
+
+class PuctSearch:
+    """AlphaZero-style Monte Carlo Tree Search guided by a policy/value evaluator"""
+
+    def __init__(
+        self,
+        evaluator: PolicyValueEvaluator,
+        n_playouts: int,
+        c_puct: float = 1.5,
+        dirichlet_alpha: Optional[float] = None,
+        dirichlet_epsilon: float = 0.25,
+        temperature: float = 0.0,
+        seed: Optional[int] = None,
+        batch_size: int = 16,
+    ) -> None: ...
+    """Initialize PuctSearch
+    Args:
+        evaluator: Policy/value evaluator
+        n_playouts: Number of playouts
+        c_puct: Exploration constant
+        dirichlet_alpha: Alpha of the Dirichlet noise mixed into the root priors. None adds no noise
+        dirichlet_epsilon: Weight of the noise
+        temperature: get_move samples moves in proportion to visits^(1/temperature).
+            0 picks the most visited move
+        seed: RNG seed. The same seed gives the same sequence of moves. None seeds from the OS
+        batch_size: Leaves evaluated per evaluate_batch call, selected with virtual losses.
+            Only used if the evaluator defines evaluate_batch
+    Raises:
+        ValueError: If c_puct or temperature is negative, dirichlet_alpha is not positive
+            or dirichlet_epsilon is not between 0 and 1
+    """
+    def get_move(self, board: Board) -> Optional[int]: ...
+    def get_move_with_timeout(self, board: Board, timeout_ms: int) -> Optional[int]: ...
+    def get_move_with_playouts(self, board: Board, n_playouts: int) -> Optional[int]: ...
+    """Move found with n_playouts playouts instead of the number given at construction"""
+    def get_move_and_policy(
+        self,
+        board: Board,
+        temperature: Optional[float] = None,
+        n_playouts: Optional[int] = None,
+    ) -> Tuple[Optional[int], List[float]]: ...
+    """Search once and return the sampled move and the root visit distribution
+    Args:
+        board: Board to search
+        temperature: Temperature for this call instead of the one given at construction
+        n_playouts: Number of playouts for this call instead of the one given at construction
+    Returns:
+        (move, policy): move is None if the player must pass or the game is over.
+        policy has 65 values indexed by square, the pass at 64, summing to 1
+    """
+    def get_search_score(self, board: Board) -> float: ...
+    def search(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        callback: Optional[Callable[[SearchResult], None]] = None,
+        callback_interval: int = 1000,
+        n_playouts: Optional[int] = None,
+    ) -> SearchResult: ...
+    """Search once. best_move is the most visited move whatever the temperature
+    Args:
+        board: Board to search
+        timeout_ms: Timeout in milliseconds. None runs every playout
+        callback: Called with the result so far every callback_interval playouts.
+            An exception raised by the callback stops the search and is re-raised
+        callback_interval: Number of playouts between callbacks
+        n_playouts: Number of playouts for this call instead of the one given at construction
+    """
+    def start_search(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        callback: Optional[Callable[[SearchResult], None]] = None,
+        callback_interval: int = 1000,
+        n_playouts: Optional[int] = None,
+    ) -> SearchHandle: ...
+    """Search in a background thread. Arguments are the same as search()"""
+    def stop(self) -> None: ...
+    """Stop every running search of this instance. They return the result so far"""
+    def get_policy(self, board: Board, n_playouts: Optional[int] = None) -> SearchPolicy: ...
+    """Search once and return the visits and winrates of the root moves
+    Args:
+        board: Board to search
+        n_playouts: Number of playouts for this call instead of the one given at construction
+    """
+
+class EndgameSolver:
+    """Exact endgame solver
+    Scores are final disc differentials for the side to move, with empty squares
+    counted for the winner. Around 20 empty squares is practical.
+    """
+
+    def __init__(self) -> None: ...
+    def solve(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        max_nodes: Optional[int] = None,
+    ) -> Tuple[int, List[int]]: ...
+    """Solve the board under perfect play
+    Args:
+        timeout_ms: Time limit in milliseconds
+        max_nodes: Maximum number of positions to search
+    Returns:
+        (score, best_moves): final disc differential and every move reaching it, sorted.
+        best_moves is empty if the player must pass or the game is over.
+    Raises:
+        TimeoutError: If a limit or stop() ends the solve before it finishes
+    """
+    def solve_win_loss(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        max_nodes: Optional[int] = None,
+    ) -> Tuple[int, List[int]]: ...
+    """Like solve, but only finds the result. Faster than solve
+    Returns:
+        (result, best_moves): 1 win, 0 draw, -1 loss, and every move reaching it.
+    """
+    def get_move(
+        self,
+        board: Board,
+        timeout_ms: Optional[int] = None,
+        max_nodes: Optional[int] = None,
+    ) -> Optional[int]: ...
+    """Lowest numbered best move, or None if the player must pass or the game is over"""
+    def stop(self) -> None: ...
+    """Stop every running solve of this instance. They raise TimeoutError"""
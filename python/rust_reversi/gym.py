@@ -0,0 +1,90 @@
+"""Gymnasium environment on top of OthelloEnv.
+
+Requires `gymnasium` and `numpy` (`pip install rust-reversi[gym]`). Importing this module
+registers the environment as "rust_reversi/Othello-v0".
+"""
+
+from typing import Any, Dict, List, Optional, Tuple
+
+import gymnasium
+import numpy as np
+from gymnasium import spaces
+
+from .rust_reversi import OthelloEnv, Turn
+
+ENV_ID = "rust_reversi/Othello-v0"
+
+
+class OthelloGymEnv(gymnasium.Env):
+    """OthelloEnv as a gymnasium.Env, played against a fixed opponent.
+
+    Observations are float32 arrays of shape (3, 8, 8): player's discs, opponent's discs
+    and legal moves, seen from the agent. Actions are 0-63 for a move and 64 for a pass.
+    An illegal action ends the episode with `illegal_action_reward` instead of raising,
+    so every action of the action space is accepted. Legal actions are given by
+    `action_masks()` and info["action_mask"].
+    """
+
+    metadata = {"render_modes": ["ansi"]}
+
+    def __init__(
+        self,
+        opponent: Optional[Any] = None,
+        agent_turn: Optional[Turn] = Turn.BLACK,
+        reward: str = "win_loss",
+        illegal_action_reward: float = -1.0,
+        render_mode: Optional[str] = None,
+    ) -> None:
+        """Initialize OthelloGymEnv
+        Args:
+            opponent: Object with `get_move(board)` such as AlphaBetaSearch, ThunderSearch
+                or MctsSearch. None plays uniformly random moves.
+            agent_turn: Color of the agent. None picks a random color on every reset.
+            reward: "win_loss" for +1/-1/0 at the end of the game,
+                "disc_diff" for the final disc difference
+            illegal_action_reward: Reward of an illegal action, which ends the episode
+            render_mode: None or "ansi"
+        """
+        if render_mode is not None and render_mode not in self.metadata["render_modes"]:
+            raise ValueError(f"Unsupported render_mode: {render_mode}")
+        self.render_mode = render_mode
+        self.env = OthelloEnv(opponent=opponent, agent_turn=agent_turn, reward=reward)
+        self.illegal_action_reward = illegal_action_reward
+        self.action_space = spaces.Discrete(65)
+        self.observation_space = spaces.Box(0.0, 1.0, (3, 8, 8), np.float32)
+        self._observation = np.zeros((3, 8, 8), dtype=np.float32)
+
+    def reset(
+        self, *, seed: Optional[int] = None, options: Optional[Dict[str, Any]] = None
+    ) -> Tuple[np.ndarray, Dict[str, Any]]:
+        super().reset(seed=seed)
+        observation, info = self.env.reset(seed=seed, options=options)
+        self._observation = np.asarray(observation, dtype=np.float32)
+        info["illegal_action"] = False
+        return self._observation, info
+
+    def step(self, action: int) -> Tuple[np.ndarray, float, bool, bool, Dict[str, Any]]:
+        action = int(action)
+        mask = self.env.action_masks()
+        if not 0 <= action < len(mask) or not mask[action]:
+            info = {"action_mask": mask, "illegal_action": True}
+            return self._observation, self.illegal_action_reward, True, False, info
+        observation, reward, terminated, truncated, info = self.env.step(action)
+        self._observation = np.asarray(observation, dtype=np.float32)
+        info["illegal_action"] = False
+        return self._observation, reward, terminated, truncated, info
+
+    def action_masks(self) -> List[bool]:
+        """Legal actions of length 65"""
+        return self.env.action_masks()
+
+    def render(self) -> Optional[str]:
+        if self.render_mode == "ansi":
+            return self.env.render()
+        return None
+
+    def close(self) -> None:
+        self.env.close()
+
+
+gymnasium.register(id=ENV_ID, entry_point=f"{__name__}:OthelloGymEnv")
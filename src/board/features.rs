@@ -0,0 +1,132 @@
+const NOT_A_FILE: u64 = 0x7F_7F_7F_7F_7F_7F_7F_7F;
+const NOT_H_FILE: u64 = 0xFE_FE_FE_FE_FE_FE_FE_FE;
+
+pub const CORNER_MASK: u64 = 0x81_00_00_00_00_00_00_81;
+pub const X_SQUARE_MASK: u64 = 0x00_42_00_00_00_00_42_00;
+pub const C_SQUARE_MASK: u64 = 0x42_81_00_00_00_00_81_42;
+pub const EDGE_MASK: u64 = 0xFF_81_81_81_81_81_81_FF;
+
+// squares that have an off-board neighbor along each axis
+const HORIZONTAL_BORDER: u64 = 0x81_81_81_81_81_81_81_81;
+const VERTICAL_BORDER: u64 = 0xFF_00_00_00_00_00_00_FF;
+
+#[inline]
+fn shift_horizontal(x: u64) -> u64 {
+    ((x << 1) & NOT_H_FILE) | ((x >> 1) & NOT_A_FILE)
+}
+
+#[inline]
+fn shift_vertical(x: u64) -> u64 {
+    (x << 8) | (x >> 8)
+}
+
+#[inline]
+fn shift_diagonal(x: u64) -> u64 {
+    ((x << 9) & NOT_H_FILE) | ((x >> 9) & NOT_A_FILE)
+}
+
+#[inline]
+fn shift_anti_diagonal(x: u64) -> u64 {
+    ((x << 7) & NOT_A_FILE) | ((x >> 7) & NOT_H_FILE)
+}
+
+/// Squares adjacent (in any of the 8 directions) to a square in `x`.
+#[inline]
+pub fn neighbors(x: u64) -> u64 {
+    shift_horizontal(x) | shift_vertical(x) | shift_diagonal(x) | shift_anti_diagonal(x)
+}
+
+/// Lines of the board: 8 rows, 8 columns, 15 diagonals and 15 anti-diagonals.
+const fn line_masks() -> [[u64; 15]; 4] {
+    let mut masks = [[0u64; 15]; 4];
+    let mut pos = 0;
+    while pos < 64 {
+        let bit = 1u64 << (63 - pos);
+        let row = pos / 8;
+        let col = pos % 8;
+        masks[0][row] |= bit;
+        masks[1][col] |= bit;
+        masks[2][row + 7 - col] |= bit;
+        masks[3][row + col] |= bit;
+        pos += 1;
+    }
+    masks
+}
+
+const LINE_MASKS: [[u64; 15]; 4] = line_masks();
+
+/// Squares whose line along each axis (horizontal, vertical, diagonal, anti-diagonal) is full.
+fn full_lines(occupied: u64) -> [u64; 4] {
+    let mut full = [0u64; 4];
+    for (axis, masks) in LINE_MASKS.iter().enumerate() {
+        for &mask in masks {
+            if mask != 0 && occupied & mask == mask {
+                full[axis] |= mask;
+            }
+        }
+    }
+    full
+}
+
+fn stable_discs_of(discs: u64, full: &[u64; 4]) -> u64 {
+    let mut stable = 0;
+    loop {
+        let horizontal = shift_horizontal(stable) | full[0] | HORIZONTAL_BORDER;
+        let vertical = shift_vertical(stable) | full[1] | VERTICAL_BORDER;
+        let diagonal = shift_diagonal(stable) | full[2] | EDGE_MASK;
+        let anti_diagonal = shift_anti_diagonal(stable) | full[3] | EDGE_MASK;
+        let new_stable = discs & horizontal & vertical & diagonal & anti_diagonal;
+        if new_stable == stable {
+            return stable;
+        }
+        stable = new_stable;
+    }
+}
+
+/// Discs that can never be flipped, as (player, opponent) bitboards.
+/// # Note
+/// * A disc is stable if, along every axis, its line is full or it touches the edge
+///   or a stable disc of the same color.
+/// * This is a conservative estimate: every reported disc is stable, but some stable
+///   discs in unusual configurations may be missed.
+pub fn stable_discs(player_board: u64, opponent_board: u64) -> (u64, u64) {
+    let full = full_lines(player_board | opponent_board);
+    (
+        stable_discs_of(player_board, &full),
+        stable_discs_of(opponent_board, &full),
+    )
+}
+
+/// Discs adjacent to at least one empty square, as (player, opponent) bitboards.
+pub fn frontier_discs(player_board: u64, opponent_board: u64) -> (u64, u64) {
+    let around_empty = neighbors(!(player_board | opponent_board));
+    (player_board & around_empty, opponent_board & around_empty)
+}
+
+/// Empty squares adjacent to the other side's discs, as (player, opponent) bitboards.
+pub fn potential_mobility(player_board: u64, opponent_board: u64) -> (u64, u64) {
+    let empty = !(player_board | opponent_board);
+    (
+        neighbors(opponent_board) & empty,
+        neighbors(player_board) & empty,
+    )
+}
+
+/// 8-connected regions of empty squares.
+pub fn empty_regions(player_board: u64, opponent_board: u64) -> Vec<u64> {
+    let mut remaining = !(player_board | opponent_board);
+    let mut regions = Vec::new();
+    while remaining != 0 {
+        let mut region = remaining & remaining.wrapping_neg();
+        loop {
+            let grown = (region | neighbors(region)) & remaining;
+            if grown == region {
+                break;
+            }
+            region = grown;
+        }
+        remaining &= !region;
+        regions.push(region);
+    }
+    regions
+}
@@ -1,13 +1,33 @@
-use pyo3::{exceptions::PyValueError, prelude::*};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyTuple};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use rust_reversi_core::board::{
     Board as RustBoard, BoardError, Color as RustColor, Turn as RustTurn,
 };
 
-#[pyclass(eq)]
+mod features;
+#[cfg(feature = "numpy")]
+mod planes;
+mod symmetry;
+mod zobrist;
+pub use features::neighbors;
+#[cfg(feature = "numpy")]
+pub use planes::fill_planes;
+pub use symmetry::{transform_bitboard, SYMMETRY_NUM};
+pub use zobrist::zobrist_hash;
+
+/// `__reduce__` value restoring the class attribute `name` of the type of `slf`.
+fn reduce_to_classattr<'py, T>(slf: &Bound<'py, T>, name: &str) -> PyResult<Bound<'py, PyTuple>> {
+    let py = slf.py();
+    let getattr = py.import("builtins")?.getattr("getattr")?;
+    let args = (slf.as_any().get_type(), name).into_pyobject(py)?;
+    PyTuple::new(py, [getattr, args.into_any()])
+}
+
+#[pyclass(eq, module = "rust_reversi")]
 #[derive(Clone, PartialEq)]
 pub struct Turn {
-    inner: RustTurn,
+    pub inner: RustTurn,
 }
 
 #[pymethods]
@@ -27,9 +47,33 @@ impl Turn {
             RustTurn::White => "White",
         }
     }
+
+    fn __repr__(&self) -> &'static str {
+        match self.inner {
+            RustTurn::Black => "Turn.BLACK",
+            RustTurn::White => "Turn.WHITE",
+        }
+    }
+
+    fn __int__(&self) -> u8 {
+        turn_to_int(self.inner)
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.__int__() as u64
+    }
+
+    /// Pickled as the class attribute of the same name.
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyTuple>> {
+        let name = match slf.borrow().inner {
+            RustTurn::Black => "BLACK",
+            RustTurn::White => "WHITE",
+        };
+        reduce_to_classattr(slf, name)
+    }
 }
 
-#[pyclass(eq)]
+#[pyclass(eq, module = "rust_reversi")]
 #[derive(Clone, PartialEq)]
 pub struct Color {
     inner: RustColor,
@@ -57,21 +101,182 @@ impl Color {
             RustColor::White => "White",
         }
     }
+
+    fn __repr__(&self) -> &'static str {
+        match self.inner {
+            RustColor::Empty => "Color.EMPTY",
+            RustColor::Black => "Color.BLACK",
+            RustColor::White => "Color.WHITE",
+        }
+    }
+
+    fn __int__(&self) -> u8 {
+        match self.inner {
+            RustColor::Empty => 0,
+            RustColor::Black => 1,
+            RustColor::White => 2,
+        }
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.__int__() as u64
+    }
+
+    /// Pickled as the class attribute of the same name.
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyTuple>> {
+        let name = match slf.borrow().inner {
+            RustColor::Empty => "EMPTY",
+            RustColor::Black => "BLACK",
+            RustColor::White => "WHITE",
+        };
+        reduce_to_classattr(slf, name)
+    }
+}
+
+fn turn_to_int(turn: RustTurn) -> u8 {
+    match turn {
+        RustTurn::Black => 0,
+        RustTurn::White => 1,
+    }
+}
+
+fn check_transform(transform: usize) -> PyResult<()> {
+    if transform >= SYMMETRY_NUM {
+        return Err(PyValueError::new_err("Invalid transform"));
+    }
+    Ok(())
+}
+
+fn int_to_turn(value: u8) -> PyResult<RustTurn> {
+    match value {
+        0 => Ok(RustTurn::Black),
+        1 => Ok(RustTurn::White),
+        _ => Err(PyValueError::new_err("Invalid turn")),
+    }
+}
+
+const BOARD_SIZE: usize = 8;
+
+/// Uniform index in `0..len`.
+/// # Note
+/// * Sampled as u32 so that seeded sequences match on 32-bit and 64-bit platforms.
+pub fn random_index<R: Rng>(rng: &mut R, len: usize) -> usize {
+    rng.gen_range(0..len as u32) as usize
+}
+
+/// Uniformly random legal move, or None if the player must pass.
+pub fn random_move<R: Rng>(board: &mut RustBoard, rng: &mut R) -> Option<usize> {
+    let legal_moves = board.get_legal_moves_vec();
+    if legal_moves.is_empty() {
+        return None;
+    }
+    Some(legal_moves[random_index(rng, legal_moves.len())])
+}
+
+fn pos_to_str(pos: usize) -> Option<String> {
+    if pos >= BOARD_SIZE * BOARD_SIZE {
+        return None;
+    }
+    let col = (b'a' + (pos % BOARD_SIZE) as u8) as char;
+    let row = (b'1' + (pos / BOARD_SIZE) as u8) as char;
+    Some(format!("{}{}", col, row))
+}
+
+fn str_to_pos(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 2 {
+        return None;
+    }
+    let col = bytes[0].to_ascii_lowercase();
+    let row = bytes[1];
+    if !(b'a'..=b'h').contains(&col) || !(b'1'..=b'8').contains(&row) {
+        return None;
+    }
+    Some((row - b'1') as usize * BOARD_SIZE + (col - b'a') as usize)
+}
+
+#[derive(Clone)]
+struct HistoryEntry {
+    pos: Option<usize>,
+    player_board: u64,
+    opponent_board: u64,
+    turn: RustTurn,
 }
 
-#[pyclass]
+#[pyclass(module = "rust_reversi")]
 #[derive(Clone)]
 pub struct Board {
     pub inner: RustBoard,
+    history: Vec<HistoryEntry>,
+    redo_stack: Vec<Option<usize>>,
+    rng: Option<StdRng>,
+}
+
+impl From<RustBoard> for Board {
+    fn from(inner: RustBoard) -> Self {
+        Board {
+            inner,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+            rng: None,
+        }
+    }
+}
+
+impl Board {
+    fn transformed(&self, transform: usize) -> Board {
+        let (player_board, opponent_board, turn) = self.inner.get_board();
+        let mut board = RustBoard::new();
+        board.set_board(
+            transform_bitboard(player_board, transform),
+            transform_bitboard(opponent_board, transform),
+            turn,
+        );
+        Board::from(board)
+    }
+
+    fn snapshot(&self, pos: Option<usize>) -> HistoryEntry {
+        let (player_board, opponent_board, turn) = self.inner.get_board();
+        HistoryEntry {
+            pos,
+            player_board,
+            opponent_board,
+            turn,
+        }
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+        self.redo_stack.clear();
+    }
+
+    fn apply_move(&mut self, pos: usize) -> PyResult<()> {
+        let entry = self.snapshot(Some(pos));
+        self.inner.do_move(pos).map_err(|e| match e {
+            BoardError::InvalidPosition => PyValueError::new_err("Invalid position"),
+            BoardError::InvalidMove => PyValueError::new_err("Invalid move"),
+            _ => PyValueError::new_err("Unexpected error"),
+        })?;
+        self.history.push(entry);
+        Ok(())
+    }
+
+    fn apply_pass(&mut self) -> PyResult<()> {
+        let entry = self.snapshot(None);
+        self.inner.do_pass().map_err(|e| match e {
+            BoardError::InvalidPass => PyValueError::new_err("Invalid pass"),
+            _ => PyValueError::new_err("Unexpected error"),
+        })?;
+        self.history.push(entry);
+        Ok(())
+    }
 }
 
 #[pymethods]
 impl Board {
     #[new]
     fn new() -> Self {
-        Board {
-            inner: RustBoard::new(),
-        }
+        Board::from(RustBoard::new())
     }
 
     fn get_board(&self) -> (u64, u64, Turn) {
@@ -91,6 +296,7 @@ impl Board {
     fn set_board(&mut self, player_board: u64, opponent_board: u64, turn: Turn) {
         self.inner
             .set_board(player_board, opponent_board, turn.inner);
+        self.clear_history();
     }
 
     fn set_board_str(&mut self, line: &str, turn: Turn) -> PyResult<()> {
@@ -99,7 +305,9 @@ impl Board {
             .map_err(|e| match e {
                 BoardError::InvalidCharactor => PyValueError::new_err("Invalid charactor"),
                 _ => PyValueError::new_err("Unexpected error"),
-            })
+            })?;
+        self.clear_history();
+        Ok(())
     }
 
     fn get_board_line(&self) -> PyResult<String> {
@@ -144,6 +352,49 @@ impl Board {
         })
     }
 
+    #[cfg(feature = "numpy")]
+    #[pyo3(signature = (history=0))]
+    fn get_board_planes<'py>(
+        &self,
+        py: Python<'py>,
+        history: usize,
+    ) -> Bound<'py, numpy::PyArray3<f32>> {
+        use numpy::{ndarray::Array3, IntoPyArray};
+        let mut array = Array3::<f32>::zeros((planes::plane_num(history), BOARD_SIZE, BOARD_SIZE));
+        planes::fill_planes(self, history, array.view_mut());
+        array.into_pyarray(py)
+    }
+
+    #[cfg(feature = "numpy")]
+    #[staticmethod]
+    #[pyo3(signature = (boards, history=0))]
+    fn stack_board_planes<'py>(
+        py: Python<'py>,
+        boards: Vec<PyRef<'py, Board>>,
+        history: usize,
+    ) -> Bound<'py, numpy::PyArray4<f32>> {
+        use numpy::{
+            ndarray::{Array4, Axis},
+            IntoPyArray,
+        };
+        let mut array = Array4::<f32>::zeros((
+            boards.len(),
+            planes::plane_num(history),
+            BOARD_SIZE,
+            BOARD_SIZE,
+        ));
+        for (board, board_array) in boards.iter().zip(array.axis_iter_mut(Axis(0))) {
+            planes::fill_planes(board, history, board_array);
+        }
+        array.into_pyarray(py)
+    }
+
+    #[cfg(feature = "numpy")]
+    fn get_legal_moves_array<'py>(&mut self, py: Python<'py>) -> Bound<'py, numpy::PyArray1<bool>> {
+        use numpy::IntoPyArray;
+        self.inner.get_legal_moves_tf().into_pyarray(py)
+    }
+
     fn player_piece_num(&self) -> i32 {
         self.inner.player_piece_num()
     }
@@ -185,27 +436,206 @@ impl Board {
     }
 
     fn get_child_boards(&mut self) -> Option<Vec<Board>> {
-        self.inner.get_child_boards().map(|board_vec| {
-            board_vec
-                .into_iter()
-                .map(|board| Board { inner: board })
-                .collect()
-        })
+        self.inner
+            .get_child_boards()
+            .map(|board_vec| board_vec.into_iter().map(Board::from).collect())
+    }
+
+    fn get_stable_discs(&self) -> (u64, u64) {
+        let (player_board, opponent_board, _) = self.inner.get_board();
+        features::stable_discs(player_board, opponent_board)
+    }
+
+    fn get_edge_stable_discs(&self) -> (u64, u64) {
+        let (player_stable, opponent_stable) = self.get_stable_discs();
+        (
+            player_stable & features::EDGE_MASK,
+            opponent_stable & features::EDGE_MASK,
+        )
+    }
+
+    fn get_frontier_discs(&self) -> (u64, u64) {
+        let (player_board, opponent_board, _) = self.inner.get_board();
+        features::frontier_discs(player_board, opponent_board)
+    }
+
+    fn get_potential_mobility(&self) -> (u64, u64) {
+        let (player_board, opponent_board, _) = self.inner.get_board();
+        features::potential_mobility(player_board, opponent_board)
+    }
+
+    fn get_corner_discs(&self) -> (u64, u64) {
+        let (player_board, opponent_board, _) = self.inner.get_board();
+        (
+            player_board & features::CORNER_MASK,
+            opponent_board & features::CORNER_MASK,
+        )
+    }
+
+    fn get_x_square_discs(&self) -> (u64, u64) {
+        let (player_board, opponent_board, _) = self.inner.get_board();
+        (
+            player_board & features::X_SQUARE_MASK,
+            opponent_board & features::X_SQUARE_MASK,
+        )
+    }
+
+    fn get_c_square_discs(&self) -> (u64, u64) {
+        let (player_board, opponent_board, _) = self.inner.get_board();
+        (
+            player_board & features::C_SQUARE_MASK,
+            opponent_board & features::C_SQUARE_MASK,
+        )
+    }
+
+    fn get_empty_regions(&self) -> Vec<u64> {
+        let (player_board, opponent_board, _) = self.inner.get_board();
+        features::empty_regions(player_board, opponent_board)
+    }
+
+    fn get_odd_empty_regions(&self) -> u64 {
+        self.get_empty_regions()
+            .into_iter()
+            .filter(|region| region.count_ones() % 2 == 1)
+            .fold(0, |acc, region| acc | region)
+    }
+
+    fn get_flips(&mut self, pos: usize) -> PyResult<u64> {
+        if pos >= BOARD_SIZE * BOARD_SIZE {
+            return Err(PyValueError::new_err("Invalid position"));
+        }
+        if !self.inner.is_legal_move(pos) {
+            return Err(PyValueError::new_err("Invalid move"));
+        }
+        let (_, opponent_board, _) = self.inner.get_board();
+        let mut board = self.inner.clone();
+        board.reverse(1u64 << (63 - pos));
+        let (_, reversed_opponent_board, _) = board.get_board();
+        Ok(opponent_board ^ reversed_opponent_board)
+    }
+
+    fn get_flips_vec(&mut self, pos: usize) -> PyResult<Vec<usize>> {
+        let flips = self.get_flips(pos)?;
+        Ok((0..BOARD_SIZE * BOARD_SIZE)
+            .filter(|&i| flips & (1u64 << (63 - i)) != 0)
+            .collect())
+    }
+
+    fn peek_move(&self, pos: usize) -> PyResult<Board> {
+        let mut board = Clone::clone(self);
+        board.do_move(pos)?;
+        Ok(board)
     }
 
     fn do_move(&mut self, pos: usize) -> PyResult<()> {
-        self.inner.do_move(pos).map_err(|e| match e {
-            BoardError::InvalidPosition => PyValueError::new_err("Invalid position"),
-            BoardError::InvalidMove => PyValueError::new_err("Invalid move"),
-            _ => PyValueError::new_err("Unexpected error"),
-        })
+        self.apply_move(pos)?;
+        self.redo_stack.clear();
+        Ok(())
     }
 
     fn do_pass(&mut self) -> PyResult<()> {
-        self.inner.do_pass().map_err(|e| match e {
-            BoardError::InvalidPass => PyValueError::new_err("Invalid pass"),
-            _ => PyValueError::new_err("Unexpected error"),
-        })
+        self.apply_pass()?;
+        self.redo_stack.clear();
+        Ok(())
+    }
+
+    fn undo(&mut self) -> PyResult<()> {
+        let entry = self
+            .history
+            .pop()
+            .ok_or_else(|| PyValueError::new_err("No move to undo"))?;
+        self.inner
+            .set_board(entry.player_board, entry.opponent_board, entry.turn);
+        self.redo_stack.push(entry.pos);
+        Ok(())
+    }
+
+    fn redo(&mut self) -> PyResult<()> {
+        let pos = *self
+            .redo_stack
+            .last()
+            .ok_or_else(|| PyValueError::new_err("No move to redo"))?;
+        match pos {
+            Some(pos) => self.apply_move(pos)?,
+            None => self.apply_pass()?,
+        }
+        self.redo_stack.pop();
+        Ok(())
+    }
+
+    fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    fn history(&self) -> Vec<Option<usize>> {
+        self.history.iter().map(|entry| entry.pos).collect()
+    }
+
+    fn move_count(&self) -> usize {
+        self.history.len()
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "pos_to_str")]
+    fn py_pos_to_str(pos: usize) -> PyResult<String> {
+        pos_to_str(pos).ok_or_else(|| PyValueError::new_err("Invalid position"))
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "str_to_pos")]
+    fn py_str_to_pos(s: &str) -> PyResult<usize> {
+        str_to_pos(s).ok_or_else(|| PyValueError::new_err(format!("Invalid move string: {}", s)))
+    }
+
+    fn do_move_str(&mut self, s: &str) -> PyResult<()> {
+        let pos = str_to_pos(s)
+            .ok_or_else(|| PyValueError::new_err(format!("Invalid move string: {}", s)))?;
+        self.do_move(pos)
+    }
+
+    #[staticmethod]
+    fn from_transcript(transcript: &str) -> PyResult<Board> {
+        let moves: Vec<char> = transcript.chars().filter(|c| !c.is_whitespace()).collect();
+        if !moves.len().is_multiple_of(2) {
+            return Err(PyValueError::new_err(
+                "Invalid transcript: length must be a multiple of 2",
+            ));
+        }
+        let mut board = Board::new();
+        for (i, chunk) in moves.chunks(2).enumerate() {
+            let s: String = chunk.iter().collect();
+            let pos = str_to_pos(&s).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Invalid transcript: bad move string {} at move {}",
+                    s,
+                    i + 1
+                ))
+            })?;
+            // transcripts omit passes, so insert them where the side to move has no legal move
+            if board.inner.is_pass() && !board.inner.is_game_over() {
+                board.do_pass()?;
+            }
+            if !board.inner.is_legal_move(pos) {
+                return Err(PyValueError::new_err(format!(
+                    "Invalid transcript: illegal move {} at move {}",
+                    s,
+                    i + 1
+                )));
+            }
+            board.do_move(pos)?;
+        }
+        Ok(board)
+    }
+
+    fn to_transcript(&self) -> String {
+        self.history
+            .iter()
+            .filter_map(|entry| entry.pos.and_then(pos_to_str))
+            .collect()
     }
 
     fn is_pass(&self) -> bool {
@@ -262,11 +692,19 @@ impl Board {
         }
     }
 
-    fn get_random_move(&mut self) -> PyResult<usize> {
-        self.inner.get_random_move().map_err(|e| match e {
-            BoardError::NoLegalMove => PyValueError::new_err("No legal move"),
-            _ => PyValueError::new_err("Unexpected error"),
-        })
+    #[pyo3(signature = (seed=None))]
+    fn get_random_move(&mut self, seed: Option<u64>) -> PyResult<usize> {
+        let pos = match (seed, self.rng.as_mut()) {
+            (Some(seed), _) => random_move(&mut self.inner, &mut StdRng::seed_from_u64(seed)),
+            (None, Some(rng)) => random_move(&mut self.inner, rng),
+            (None, None) => random_move(&mut self.inner, &mut rand::thread_rng()),
+        };
+        pos.ok_or_else(|| PyValueError::new_err("No legal move"))
+    }
+
+    #[pyo3(signature = (seed=None))]
+    fn set_rng_seed(&mut self, seed: Option<u64>) {
+        self.rng = seed.map(StdRng::seed_from_u64);
     }
 
     fn __str__(&self) -> PyResult<String> {
@@ -277,8 +715,153 @@ impl Board {
     }
 
     fn clone(&self) -> Self {
-        Board {
-            inner: self.inner.clone(),
+        Clone::clone(self)
+    }
+
+    fn rotate90(&self) -> Board {
+        self.transformed(1)
+    }
+
+    fn flip_horizontal(&self) -> Board {
+        self.transformed(4)
+    }
+
+    fn flip_vertical(&self) -> Board {
+        self.transformed(5)
+    }
+
+    fn flip_diagonal(&self) -> Board {
+        self.transformed(6)
+    }
+
+    fn transform(&self, transform: usize) -> PyResult<Board> {
+        check_transform(transform)?;
+        Ok(self.transformed(transform))
+    }
+
+    fn symmetries(&self) -> Vec<Board> {
+        (0..SYMMETRY_NUM).map(|t| self.transformed(t)).collect()
+    }
+
+    fn canonical(&self) -> (Board, usize) {
+        let (player_board, opponent_board, _) = self.inner.get_board();
+        let transform = (0..SYMMETRY_NUM)
+            .min_by_key(|&t| {
+                (
+                    transform_bitboard(player_board, t),
+                    transform_bitboard(opponent_board, t),
+                )
+            })
+            .unwrap();
+        (self.transformed(transform), transform)
+    }
+
+    #[staticmethod]
+    fn transform_pos(pos: usize, transform: usize) -> PyResult<usize> {
+        check_transform(transform)?;
+        if pos >= BOARD_SIZE * BOARD_SIZE {
+            return Err(PyValueError::new_err("Invalid position"));
         }
+        Ok(symmetry::transform_pos(pos, transform))
+    }
+
+    #[staticmethod]
+    fn inverse_transform(transform: usize) -> PyResult<usize> {
+        check_transform(transform)?;
+        Ok(symmetry::inverse_transform(transform))
+    }
+
+    fn hash(&self) -> u64 {
+        zobrist_hash(&self.inner)
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.hash()
+    }
+
+    fn __eq__(&self, other: &Board) -> bool {
+        self.inner.get_board() == other.inner.get_board()
+    }
+
+    fn __copy__(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyAny>) -> Self {
+        Clone::clone(self)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn __getstate__(
+        &self,
+    ) -> (
+        u64,
+        u64,
+        u8,
+        Vec<(Option<usize>, u64, u64, u8)>,
+        Vec<Option<usize>>,
+    ) {
+        let (player_board, opponent_board, turn) = self.inner.get_board();
+        let history = self
+            .history
+            .iter()
+            .map(|entry| {
+                (
+                    entry.pos,
+                    entry.player_board,
+                    entry.opponent_board,
+                    turn_to_int(entry.turn),
+                )
+            })
+            .collect();
+        (
+            player_board,
+            opponent_board,
+            turn_to_int(turn),
+            history,
+            self.redo_stack.clone(),
+        )
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn __setstate__(
+        &mut self,
+        state: (
+            u64,
+            u64,
+            u8,
+            Vec<(Option<usize>, u64, u64, u8)>,
+            Vec<Option<usize>>,
+        ),
+    ) -> PyResult<()> {
+        let (player_board, opponent_board, turn, history, redo_stack) = state;
+        self.inner
+            .set_board(player_board, opponent_board, int_to_turn(turn)?);
+        self.history = history
+            .into_iter()
+            .map(|(pos, player_board, opponent_board, turn)| {
+                Ok(HistoryEntry {
+                    pos,
+                    player_board,
+                    opponent_board,
+                    turn: int_to_turn(turn)?,
+                })
+            })
+            .collect::<PyResult<_>>()?;
+        self.redo_stack = redo_stack;
+        Ok(())
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyTuple>> {
+        let py = slf.py();
+        let state = slf.borrow().__getstate__().into_pyobject(py)?;
+        PyTuple::new(
+            py,
+            [
+                slf.get_type().into_any(),
+                PyTuple::empty(py).into_any(),
+                state.into_any(),
+            ],
+        )
     }
 }
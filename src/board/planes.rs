@@ -0,0 +1,46 @@
+use numpy::ndarray::{ArrayViewMut3, Axis};
+
+use super::Board;
+
+pub const BASE_PLANES: usize = 3;
+
+fn fill_bitboard(plane: &mut numpy::ndarray::ArrayViewMut2<f32>, bitboard: u64) {
+    for (i, cell) in plane.iter_mut().enumerate() {
+        if bitboard & (1u64 << (63 - i)) != 0 {
+            *cell = 1.0;
+        }
+    }
+}
+
+/// Number of planes written by `fill_planes`.
+pub fn plane_num(history: usize) -> usize {
+    BASE_PLANES + 2 * history
+}
+
+/// Fill zero-initialized planes of shape (plane_num(history), 8, 8).
+/// # Note
+/// * 0: player's discs, 1: opponent's discs, 2: legal moves
+/// * then for each of the last `history` positions: player's discs, opponent's discs,
+///   seen from the side to move in the current position
+/// * planes of positions before the start of the history stay zero
+pub fn fill_planes(board: &Board, history: usize, mut planes: ArrayViewMut3<f32>) {
+    let mut inner = board.inner.clone();
+    let (player_board, opponent_board, turn) = inner.get_board();
+    let legal_moves = inner.get_legal_moves();
+    fill_bitboard(&mut planes.index_axis_mut(Axis(0), 0), player_board);
+    fill_bitboard(&mut planes.index_axis_mut(Axis(0), 1), opponent_board);
+    fill_bitboard(&mut planes.index_axis_mut(Axis(0), 2), legal_moves);
+    for (k, entry) in board.history.iter().rev().take(history).enumerate() {
+        let (player_board, opponent_board) = if entry.turn == turn {
+            (entry.player_board, entry.opponent_board)
+        } else {
+            (entry.opponent_board, entry.player_board)
+        };
+        let index = BASE_PLANES + 2 * k;
+        fill_bitboard(&mut planes.index_axis_mut(Axis(0), index), player_board);
+        fill_bitboard(
+            &mut planes.index_axis_mut(Axis(0), index + 1),
+            opponent_board,
+        );
+    }
+}
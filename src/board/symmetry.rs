@@ -0,0 +1,79 @@
+/// Number of board symmetries (rotations and reflections).
+pub const SYMMETRY_NUM: usize = 8;
+
+/// Mirror rows: (row, col) -> (7 - row, col)
+#[inline]
+pub fn flip_vertical(x: u64) -> u64 {
+    x.swap_bytes()
+}
+
+/// Mirror columns: (row, col) -> (row, 7 - col)
+#[inline]
+pub fn flip_horizontal(x: u64) -> u64 {
+    const K1: u64 = 0x55_55_55_55_55_55_55_55;
+    const K2: u64 = 0x33_33_33_33_33_33_33_33;
+    const K4: u64 = 0x0F_0F_0F_0F_0F_0F_0F_0F;
+    let x = ((x >> 1) & K1) | ((x & K1) << 1);
+    let x = ((x >> 2) & K2) | ((x & K2) << 2);
+    ((x >> 4) & K4) | ((x & K4) << 4)
+}
+
+/// Transpose: (row, col) -> (col, row)
+#[inline]
+pub fn flip_diagonal(x: u64) -> u64 {
+    const K1: u64 = 0x55_00_55_00_55_00_55_00;
+    const K2: u64 = 0x33_33_00_00_33_33_00_00;
+    const K4: u64 = 0x0F_0F_0F_0F_00_00_00_00;
+    let mut x = x;
+    let t = K4 & (x ^ (x << 28));
+    x ^= t ^ (t >> 28);
+    let t = K2 & (x ^ (x << 14));
+    x ^= t ^ (t >> 14);
+    let t = K1 & (x ^ (x << 7));
+    x ^= t ^ (t >> 7);
+    x
+}
+
+/// Rotate clockwise: (row, col) -> (col, 7 - row)
+#[inline]
+pub fn rotate90(x: u64) -> u64 {
+    flip_horizontal(flip_diagonal(x))
+}
+
+/// Apply the symmetry with the given index to a bitboard.
+/// # Note
+/// * 0: identity
+/// * 1: rotate 90 degrees clockwise
+/// * 2: rotate 180 degrees
+/// * 3: rotate 270 degrees clockwise
+/// * 4: flip horizontal
+/// * 5: flip vertical
+/// * 6: flip diagonal (a1-h8)
+/// * 7: flip anti-diagonal (h1-a8)
+pub fn transform_bitboard(x: u64, transform: usize) -> u64 {
+    match transform {
+        0 => x,
+        1 => rotate90(x),
+        2 => x.reverse_bits(),
+        3 => rotate90(x.reverse_bits()),
+        4 => flip_horizontal(x),
+        5 => flip_vertical(x),
+        6 => flip_diagonal(x),
+        7 => flip_diagonal(x.reverse_bits()),
+        _ => unreachable!("invalid transform index"),
+    }
+}
+
+/// Index of the symmetry that undoes `transform`.
+pub fn inverse_transform(transform: usize) -> usize {
+    match transform {
+        1 => 3,
+        3 => 1,
+        t => t,
+    }
+}
+
+/// Map a position index through the symmetry with the given index.
+pub fn transform_pos(pos: usize, transform: usize) -> usize {
+    transform_bitboard(1u64 << (63 - pos), transform).leading_zeros() as usize
+}
@@ -0,0 +1,62 @@
+use rust_reversi_core::board::{Board as RustBoard, Turn as RustTurn};
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+/// Random keys indexed by [color][byte index][byte value].
+/// Hashing a byte of the bitboard at a time needs 16 table lookups per position
+/// instead of one per disc.
+static ZOBRIST_TABLE: [[[u64; 256]; 8]; 2] = {
+    let mut table = [[[0u64; 256]; 8]; 2];
+    let mut state = 0x5265_7665_7273_6921;
+    let mut color = 0;
+    while color < 2 {
+        let mut index = 0;
+        while index < 8 {
+            let mut value = 1;
+            while value < 256 {
+                let (next_state, key) = splitmix64(state);
+                state = next_state;
+                table[color][index][value] = key;
+                value += 1;
+            }
+            index += 1;
+        }
+        color += 1;
+    }
+    table
+};
+
+const ZOBRIST_WHITE_TO_MOVE: u64 = splitmix64(0x7475_726E_5768_6974).1;
+
+#[inline]
+fn hash_bitboard(bitboard: u64, keys: &[[u64; 256]; 8]) -> u64 {
+    let mut hash = 0;
+    for (i, key) in keys.iter().enumerate() {
+        hash ^= key[((bitboard >> (i * 8)) & 0xFF) as usize];
+    }
+    hash
+}
+
+/// Zobrist hash of the position.
+/// # Note
+/// * The keys are fixed, so the hash is stable across processes and platforms.
+/// * Only discs and side to move are hashed, so transpositions hash equally.
+pub fn zobrist_hash(board: &RustBoard) -> u64 {
+    let (player_board, opponent_board, turn) = board.get_board();
+    let (black_board, white_board) = match turn {
+        RustTurn::Black => (player_board, opponent_board),
+        RustTurn::White => (opponent_board, player_board),
+    };
+    let mut hash = hash_bitboard(black_board, &ZOBRIST_TABLE[0])
+        ^ hash_bitboard(white_board, &ZOBRIST_TABLE[1]);
+    if turn == RustTurn::White {
+        hash ^= ZOBRIST_WHITE_TO_MOVE;
+    }
+    hash
+}
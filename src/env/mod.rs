@@ -0,0 +1,436 @@
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyTuple},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[cfg(feature = "numpy")]
+use crate::board::fill_planes;
+use crate::board::{random_move, Board, Turn};
+use rust_reversi_core::board::{Board as RustBoard, BoardError, Turn as RustTurn};
+
+/// Action index used for a pass.
+pub const PASS_ACTION: usize = 64;
+
+fn check_action(board: &mut RustBoard, action: usize) -> Result<(), BoardError> {
+    if board.is_game_over() {
+        return Ok(());
+    }
+    match action {
+        PASS_ACTION if !board.is_pass() => Err(BoardError::InvalidPass),
+        PASS_ACTION => Ok(()),
+        a if a > PASS_ACTION => Err(BoardError::InvalidPosition),
+        a if !board.is_legal_move(a) => Err(BoardError::InvalidMove),
+        _ => Ok(()),
+    }
+}
+
+/// Apply an action checked by `check_action`. Finished boards are left as they are.
+fn step_board(board: &mut RustBoard, action: usize, auto_pass: bool) {
+    if board.is_game_over() {
+        return;
+    }
+    if action == PASS_ACTION {
+        board.do_pass().unwrap();
+    } else {
+        board.do_move(action).unwrap();
+    }
+    if auto_pass && board.is_pass() && !board.is_game_over() {
+        board.do_pass().unwrap();
+    }
+}
+
+fn final_reward(board: &RustBoard, turn: RustTurn) -> f64 {
+    if !board.is_game_over() {
+        return 0.0;
+    }
+    match board.get_winner() {
+        Ok(Some(winner)) if winner == turn => 1.0,
+        Ok(Some(_)) => -1.0,
+        _ => 0.0,
+    }
+}
+
+#[pyclass(module = "rust_reversi")]
+pub struct BoardBatch {
+    boards: Vec<RustBoard>,
+    auto_pass: bool,
+}
+
+impl BoardBatch {
+    fn check_index(&self, index: usize) -> PyResult<()> {
+        if index >= self.boards.len() {
+            return Err(PyValueError::new_err("Index out of range"));
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl BoardBatch {
+    #[new]
+    #[pyo3(signature = (n, auto_pass=true))]
+    fn new(n: usize, auto_pass: bool) -> Self {
+        BoardBatch {
+            boards: vec![RustBoard::new(); n],
+            auto_pass,
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.boards.len()
+    }
+
+    fn step(&mut self, py: Python<'_>, actions: Vec<usize>) -> PyResult<()> {
+        if actions.len() != self.boards.len() {
+            return Err(PyValueError::new_err(format!(
+                "Expected {} actions, got {}",
+                self.boards.len(),
+                actions.len()
+            )));
+        }
+        let auto_pass = self.auto_pass;
+        let boards = &mut self.boards;
+        py.allow_threads(|| {
+            // validate every action first so that a bad action leaves the batch untouched
+            for (i, (board, &action)) in boards.iter_mut().zip(actions.iter()).enumerate() {
+                check_action(board, action).map_err(|e| (i, e))?;
+            }
+            for (board, &action) in boards.iter_mut().zip(actions.iter()) {
+                step_board(board, action, auto_pass);
+            }
+            Ok(())
+        })
+        .map_err(|(i, e)| match e {
+            BoardError::InvalidPosition => {
+                PyValueError::new_err(format!("Invalid position on board {}", i))
+            }
+            BoardError::InvalidMove => {
+                PyValueError::new_err(format!("Invalid move on board {}", i))
+            }
+            BoardError::InvalidPass => {
+                PyValueError::new_err(format!("Invalid pass on board {}", i))
+            }
+            _ => PyValueError::new_err("Unexpected error"),
+        })
+    }
+
+    fn legal_moves_mask(&mut self) -> Vec<Vec<bool>> {
+        self.boards
+            .iter_mut()
+            .map(|board| {
+                let mut mask = board.get_legal_moves_tf();
+                mask.push(board.is_pass() && !board.is_game_over());
+                mask
+            })
+            .collect()
+    }
+
+    fn dones(&self) -> Vec<bool> {
+        self.boards
+            .iter()
+            .map(|board| board.is_game_over())
+            .collect()
+    }
+
+    #[pyo3(signature = (turn=None))]
+    fn rewards(&self, turn: Option<Turn>) -> Vec<f64> {
+        let turn = turn.map_or(RustTurn::Black, |t| t.inner);
+        self.boards
+            .iter()
+            .map(|board| final_reward(board, turn))
+            .collect()
+    }
+
+    fn turns(&self) -> Vec<Turn> {
+        self.boards
+            .iter()
+            .map(|board| Turn {
+                inner: board.get_turn(),
+            })
+            .collect()
+    }
+
+    #[pyo3(signature = (indices=None))]
+    fn reset(&mut self, indices: Option<Vec<usize>>) -> PyResult<()> {
+        match indices {
+            Some(indices) => {
+                for &i in &indices {
+                    self.check_index(i)?;
+                }
+                for i in indices {
+                    self.boards[i] = RustBoard::new();
+                }
+            }
+            None => self.boards.fill(RustBoard::new()),
+        }
+        Ok(())
+    }
+
+    fn get_board(&self, index: usize) -> PyResult<Board> {
+        self.check_index(index)?;
+        Ok(Board::from(self.boards[index].clone()))
+    }
+
+    fn get_boards(&self) -> Vec<Board> {
+        self.boards.iter().cloned().map(Board::from).collect()
+    }
+
+    fn set_board(&mut self, index: usize, board: &Board) -> PyResult<()> {
+        self.check_index(index)?;
+        self.boards[index] = board.inner.clone();
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum RewardMode {
+    WinLoss,
+    DiscDiff,
+}
+
+#[pyclass(module = "rust_reversi")]
+pub struct OthelloEnv {
+    board: RustBoard,
+    agent_turn: Option<RustTurn>,
+    agent: RustTurn,
+    opponent: Option<Py<PyAny>>,
+    reward_mode: RewardMode,
+    rng: StdRng,
+    // gymnasium spaces, built on first access
+    action_space: Option<PyObject>,
+    observation_space: Option<PyObject>,
+}
+
+impl OthelloEnv {
+    fn action_mask(&mut self) -> Vec<bool> {
+        let mut mask = self.board.get_legal_moves_tf();
+        mask.push(self.board.is_pass() && !self.board.is_game_over());
+        mask
+    }
+
+    /// Planes of `Board.get_board_planes()` as a float32 array of shape (3, 8, 8).
+    #[cfg(feature = "numpy")]
+    fn observation(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        use numpy::{ndarray::Array3, IntoPyArray};
+        let mut planes = Array3::<f32>::zeros((3, 8, 8));
+        fill_planes(&Board::from(self.board.clone()), 0, planes.view_mut());
+        Ok(planes.into_pyarray(py).into_any().unbind())
+    }
+
+    /// Same planes as nested lists when built without the numpy feature.
+    #[cfg(not(feature = "numpy"))]
+    fn observation(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        let (player_board, opponent_board, _) = self.board.get_board();
+        let legal_moves = self.board.get_legal_moves();
+        let planes: Vec<Vec<Vec<f32>>> = [player_board, opponent_board, legal_moves]
+            .iter()
+            .map(|&bitboard| {
+                (0..8)
+                    .map(|row| {
+                        (0..8)
+                            .map(|col| ((bitboard >> (63 - (row * 8 + col))) & 1) as f32)
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(planes.into_pyobject(py)?.into_any().unbind())
+    }
+
+    fn info(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        let info = PyDict::new(py);
+        info.set_item("action_mask", self.action_mask())?;
+        info.set_item(
+            "turn",
+            Turn {
+                inner: self.board.get_turn(),
+            },
+        )?;
+        info.set_item("agent_turn", Turn { inner: self.agent })?;
+        Ok(info.into_any().unbind())
+    }
+
+    fn reward(&self) -> f64 {
+        if !self.board.is_game_over() {
+            return 0.0;
+        }
+        let diff = if self.board.get_turn() == self.agent {
+            self.board.diff_piece_num()
+        } else {
+            -self.board.diff_piece_num()
+        };
+        match self.reward_mode {
+            RewardMode::WinLoss => diff.signum() as f64,
+            RewardMode::DiscDiff => diff as f64,
+        }
+    }
+
+    fn opponent_move(&mut self, py: Python<'_>) -> PyResult<usize> {
+        match &self.opponent {
+            None => Ok(random_move(&mut self.board, &mut self.rng).unwrap()),
+            Some(opponent) => {
+                let board = Board::from(self.board.clone());
+                let result = opponent.call_method1(py, "get_move", (board,))?;
+                let pos: Option<usize> = result.extract(py)?;
+                pos.ok_or_else(|| PyValueError::new_err("Opponent returned no move"))
+            }
+        }
+    }
+
+    /// Let the opponent play until it is the agent's turn or the game is over.
+    fn play_opponent(&mut self, py: Python<'_>) -> PyResult<()> {
+        while !self.board.is_game_over() && self.board.get_turn() != self.agent {
+            if self.board.is_pass() {
+                self.board.do_pass().unwrap();
+                continue;
+            }
+            let pos = self.opponent_move(py)?;
+            self.board.do_move(pos).map_err(|_| {
+                PyValueError::new_err(format!("Opponent played invalid move {}", pos))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl OthelloEnv {
+    #[classattr]
+    fn metadata(py: Python<'_>) -> PyResult<PyObject> {
+        let metadata = PyDict::new(py);
+        metadata.set_item("render_modes", vec!["ansi"])?;
+        Ok(metadata.into_any().unbind())
+    }
+
+    #[classattr]
+    fn render_mode() -> &'static str {
+        "ansi"
+    }
+
+    #[new]
+    #[pyo3(signature = (opponent=None, agent_turn=Some(Turn { inner: RustTurn::Black }), reward="win_loss", seed=None))]
+    fn new(
+        opponent: Option<Py<PyAny>>,
+        agent_turn: Option<Turn>,
+        reward: &str,
+        seed: Option<u64>,
+    ) -> PyResult<Self> {
+        let reward_mode = match reward {
+            "win_loss" => RewardMode::WinLoss,
+            "disc_diff" => RewardMode::DiscDiff,
+            _ => {
+                return Err(PyValueError::new_err(
+                    "reward must be \"win_loss\" or \"disc_diff\"",
+                ))
+            }
+        };
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let agent_turn = agent_turn.map(|t| t.inner);
+        Ok(OthelloEnv {
+            board: RustBoard::new(),
+            agent_turn,
+            agent: agent_turn.unwrap_or(RustTurn::Black),
+            opponent,
+            reward_mode,
+            rng,
+            action_space: None,
+            observation_space: None,
+        })
+    }
+
+    #[getter]
+    fn action_space(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        if self.action_space.is_none() {
+            let spaces = py.import("gymnasium.spaces")?;
+            self.action_space = Some(spaces.call_method1("Discrete", (65,))?.unbind());
+        }
+        Ok(self.action_space.as_ref().unwrap().clone_ref(py))
+    }
+
+    #[getter]
+    fn observation_space(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        if self.observation_space.is_none() {
+            let spaces = py.import("gymnasium.spaces")?;
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("low", 0.0)?;
+            kwargs.set_item("high", 1.0)?;
+            kwargs.set_item("shape", (3, 8, 8))?;
+            kwargs.set_item("dtype", "float32")?;
+            self.observation_space = Some(spaces.call_method("Box", (), Some(&kwargs))?.unbind());
+        }
+        Ok(self.observation_space.as_ref().unwrap().clone_ref(py))
+    }
+
+    #[getter]
+    fn unwrapped(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[getter]
+    fn board(&self) -> Board {
+        Board::from(self.board.clone())
+    }
+
+    #[pyo3(signature = (*, seed=None, options=None))]
+    fn reset<'py>(
+        &mut self,
+        py: Python<'py>,
+        seed: Option<u64>,
+        options: Option<Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyTuple>> {
+        let _ = options;
+        if let Some(seed) = seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+        self.board = RustBoard::new();
+        self.agent = match self.agent_turn {
+            Some(turn) => turn,
+            None if self.rng.gen_bool(0.5) => RustTurn::Black,
+            None => RustTurn::White,
+        };
+        self.play_opponent(py)?;
+        let observation = self.observation(py)?;
+        let info = self.info(py)?;
+        (observation, info).into_pyobject(py)
+    }
+
+    fn step<'py>(&mut self, py: Python<'py>, action: usize) -> PyResult<Bound<'py, PyTuple>> {
+        if self.board.is_game_over() {
+            return Err(PyValueError::new_err("Game is over, call reset()"));
+        }
+        if action == PASS_ACTION {
+            self.board
+                .do_pass()
+                .map_err(|_| PyValueError::new_err("Invalid pass"))?;
+        } else {
+            self.board.do_move(action).map_err(|e| match e {
+                BoardError::InvalidPosition => PyValueError::new_err("Invalid position"),
+                BoardError::InvalidMove => PyValueError::new_err("Invalid move"),
+                _ => PyValueError::new_err("Unexpected error"),
+            })?;
+        }
+        self.play_opponent(py)?;
+        let observation = self.observation(py)?;
+        let info = self.info(py)?;
+        let terminated = self.board.is_game_over();
+        (observation, self.reward(), terminated, false, info).into_pyobject(py)
+    }
+
+    fn action_masks(&mut self) -> Vec<bool> {
+        self.action_mask()
+    }
+
+    fn render(&self) -> PyResult<String> {
+        self.board.to_string().map_err(|e| match e {
+            BoardError::InvalidState => PyValueError::new_err("Invalid state"),
+            _ => PyValueError::new_err("Unexpected error"),
+        })
+    }
+
+    fn close(&self) {}
+}
@@ -6,10 +6,14 @@ use board::{Board, Color, Turn};
 mod arena;
 use arena::{Arena, NetworkArenaClient, NetworkArenaServer};
 
+mod env;
+use env::{BoardBatch, OthelloEnv};
+
 mod search;
 use search::{
-    AlphaBetaSearch, Evaluator, LegalNumEvaluator, MatrixEvaluator, MctsSearch, PieceEvaluator,
-    ThunderSearch, WinrateEvaluator,
+    AlphaBetaSearch, EndgameSolver, Evaluator, LegalNumEvaluator, MatrixEvaluator, MctsSearch,
+    MoveAnalysis, PieceEvaluator, PolicyValueEvaluator, PuctSearch, RolloutPolicy, SearchHandle,
+    SearchPolicy, SearchResult, ThunderSearch, WinrateEvaluator,
 };
 
 #[pymodule]
@@ -17,6 +21,8 @@ fn rust_reversi(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Turn>()?;
     m.add_class::<Color>()?;
     m.add_class::<Board>()?;
+    m.add_class::<BoardBatch>()?;
+    m.add_class::<OthelloEnv>()?;
     m.add_class::<Arena>()?;
     m.add_class::<NetworkArenaClient>()?;
     m.add_class::<NetworkArenaServer>()?;
@@ -28,5 +34,13 @@ fn rust_reversi(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<ThunderSearch>()?;
     m.add_class::<WinrateEvaluator>()?;
     m.add_class::<MctsSearch>()?;
+    m.add_class::<RolloutPolicy>()?;
+    m.add_class::<PolicyValueEvaluator>()?;
+    m.add_class::<PuctSearch>()?;
+    m.add_class::<EndgameSolver>()?;
+    m.add_class::<SearchResult>()?;
+    m.add_class::<SearchHandle>()?;
+    m.add_class::<SearchPolicy>()?;
+    m.add_class::<MoveAnalysis>()?;
     Ok(())
 }
@@ -0,0 +1,662 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rust_reversi_core::board::Board;
+use rust_reversi_core::search::{Evaluator, Search};
+
+use super::batch::BatchEvaluator;
+use super::control::{ControlledSearch, SearchControl};
+use super::result::{MoveAnalysis, SearchResult};
+use super::time_keeper::TimeKeeper;
+use super::transposition::{Bound, TranspositionTable, TtEntry};
+use crate::board::zobrist_hash;
+
+/// State shared by every node of one thread during one search.
+#[derive(Default)]
+struct AlphaBetaContext<'a> {
+    nodes: u64,
+    time_keeper: Option<&'a TimeKeeper>,
+    max_nodes: Option<u64>,
+    /// Set by the main thread to stop helper threads.
+    stop: Option<&'a AtomicBool>,
+    /// Helper threads rotate the root moves by this to diverge from the main thread.
+    thread_id: usize,
+    aborted: bool,
+}
+
+impl AlphaBetaContext<'_> {
+    /// Whether the search must stop. Results of an aborted search are incomplete.
+    fn check_abort(&mut self) -> bool {
+        if let Some(time_keeper) = self.time_keeper {
+            if time_keeper.is_timeout() {
+                self.aborted = true;
+            }
+        }
+        if self
+            .max_nodes
+            .is_some_and(|max_nodes| self.nodes >= max_nodes)
+        {
+            self.aborted = true;
+        }
+        if let Some(stop) = self.stop {
+            if stop.load(Ordering::Relaxed) {
+                self.aborted = true;
+            }
+        }
+        self.aborted
+    }
+}
+
+/// Alpha-beta search.
+/// # Note
+/// * Same algorithm as `rust_reversi_core::search::AlphaBetaSearch`, which also
+///   reports the principal variation and search statistics.
+/// * An optional transposition table is used for cutoffs and move ordering.
+/// * With more than one thread, helper threads search the same position
+///   (Lazy SMP) and share their results through the transposition table.
+/// * With a batch evaluator, the children of nodes one move above the leaves
+///   are evaluated together in one call.
+#[derive(Debug)]
+pub struct AlphaBetaSearch {
+    max_depth: usize,
+    evaluator: Arc<dyn Evaluator>,
+    batch_evaluator: Option<Arc<dyn BatchEvaluator>>,
+    win_score: i32,
+    margin_time: f64,
+    tt: Option<TranspositionTable>,
+    persist_tt: AtomicBool,
+    threads: usize,
+}
+
+impl AlphaBetaSearch {
+    /// Create a new AlphaBetaSearch instance.
+    /// # Arguments
+    /// * `max_depth` - The maximum depth of the search tree.
+    /// * `evaluator` - The evaluator to evaluate the board.
+    /// * `win_score` - The score of the win.
+    /// * `tt_size_mb` - Size of the transposition table in megabytes. 0 disables it.
+    /// # Note
+    /// * The win_score must be greater than any possible score.
+    pub fn new(
+        max_depth: usize,
+        evaluator: Arc<dyn Evaluator>,
+        win_score: i32,
+        tt_size_mb: usize,
+    ) -> Self {
+        Self {
+            max_depth,
+            evaluator,
+            batch_evaluator: None,
+            win_score,
+            margin_time: DEFAULT_MARGIN_TIME,
+            tt: (tt_size_mb > 0).then(|| TranspositionTable::new(tt_size_mb)),
+            persist_tt: AtomicBool::new(false),
+            threads: 1,
+        }
+    }
+
+    /// Set the number of search threads.
+    /// # Note
+    /// * Helper threads need the transposition table, so without it the search is single-threaded.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Evaluate leaves in batches with `batch_evaluator`.
+    /// # Note
+    /// * `evaluator` is still used for move ordering, so both should give the same scores.
+    pub fn set_batch_evaluator(&mut self, batch_evaluator: Arc<dyn BatchEvaluator>) {
+        self.batch_evaluator = Some(batch_evaluator);
+    }
+
+    pub fn persist_tt(&self) -> bool {
+        self.persist_tt.load(Ordering::Relaxed)
+    }
+
+    /// Keep transposition table entries from one call to the next,
+    /// e.g. while searching positions of the same game.
+    pub fn set_persist_tt(&self, persist_tt: bool) {
+        self.persist_tt.store(persist_tt, Ordering::Relaxed);
+    }
+
+    pub fn clear_tt(&self) {
+        if let Some(tt) = &self.tt {
+            tt.clear();
+        }
+    }
+
+    fn start_search(&self) {
+        if let Some(tt) = &self.tt {
+            tt.new_search(self.persist_tt());
+        }
+    }
+
+    fn probe_tt(&self, key: u64) -> Option<TtEntry> {
+        self.tt.as_ref().and_then(|tt| tt.probe(key))
+    }
+
+    fn store_tt(&self, key: u64, entry: TtEntry) {
+        if let Some(tt) = &self.tt {
+            tt.store(key, entry);
+        }
+    }
+
+    /// Store the result of a node searched with `alpha` and `beta`, unless the search was aborted.
+    fn store_result(
+        &self,
+        key: u64,
+        depth: usize,
+        score: i32,
+        beta: i32,
+        best_move: Option<usize>,
+        ctx: &AlphaBetaContext,
+    ) {
+        if ctx.aborted {
+            return;
+        }
+        let bound = if score >= beta {
+            Bound::Lower
+        } else if best_move.is_some() {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        self.store_tt(
+            key,
+            TtEntry {
+                score,
+                depth,
+                bound,
+                best_move,
+            },
+        );
+    }
+
+    /// Follow the best moves stored in the transposition table for `depth` moves.
+    fn tt_pv(&self, board: &Board, depth: usize) -> Vec<Option<usize>> {
+        let mut board = board.clone();
+        let mut pv = Vec::new();
+        let mut moves = 0;
+        while moves < depth && !board.is_game_over() {
+            if board.is_pass() {
+                board.do_pass().unwrap();
+                pv.push(None);
+                continue;
+            }
+            let Some(move_i) = self
+                .probe_tt(zobrist_hash(&board))
+                .and_then(|e| e.best_move)
+            else {
+                break;
+            };
+            if board.do_move(move_i).is_err() {
+                break;
+            }
+            pv.push(Some(move_i));
+            moves += 1;
+        }
+        pv
+    }
+
+    fn terminal_score(&self, board: &Board) -> i32 {
+        match (board.is_win(), board.is_lose()) {
+            (Ok(true), _) => self.win_score,
+            (_, Ok(true)) => -self.win_score,
+            _ => 0,
+        }
+    }
+
+    fn score_board(&self, board: &mut Board) -> i32 {
+        if board.is_game_over() {
+            return self.terminal_score(board);
+        }
+        self.evaluator.evaluate(board)
+    }
+
+    fn get_legal_moves_ordered(&self, board: &mut Board) -> Vec<usize> {
+        let mut legal_moves = board.get_legal_moves_vec().to_vec();
+        legal_moves.sort_by_key(|&m| {
+            let mut new_board = board.clone();
+            new_board.do_move(m).unwrap();
+            self.score_board(&mut new_board)
+        });
+        legal_moves
+    }
+
+    fn negamax(
+        &self,
+        board: &mut Board,
+        depth: usize,
+        alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Option<usize>>,
+        ctx: &mut AlphaBetaContext,
+    ) -> i32 {
+        ctx.nodes += 1;
+        if board.is_game_over() {
+            return self.terminal_score(board);
+        }
+        if depth == 0 {
+            return self.evaluator.evaluate(board);
+        }
+
+        if board.is_pass() {
+            let mut new_board = board.clone();
+            new_board.do_pass().unwrap();
+            let mut child_pv = Vec::new();
+            let score = -self.negamax(&mut new_board, depth, -beta, -alpha, &mut child_pv, ctx);
+            pv.clear();
+            pv.push(None);
+            pv.extend(child_pv);
+            return score;
+        }
+
+        let key = zobrist_hash(board);
+        let tt_entry = self.probe_tt(key);
+        if let Some(entry) = tt_entry.filter(|e| e.depth >= depth) {
+            let cut = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => entry.score >= beta,
+                Bound::Upper => entry.score <= alpha,
+            };
+            if cut {
+                if entry.bound == Bound::Exact {
+                    *pv = self.tt_pv(board, depth);
+                }
+                return entry.score;
+            }
+        }
+
+        if let (1, Some(batch_evaluator)) = (depth, &self.batch_evaluator) {
+            let (score, best_move) =
+                self.negamax_frontier(board, alpha, pv, ctx, batch_evaluator.as_ref());
+            self.store_result(key, depth, score, beta, best_move, ctx);
+            return score;
+        }
+
+        let mut legal_moves = match (depth > 2, board.get_legal_moves().count_ones() > 4) {
+            (true, true) => self.get_legal_moves_ordered(board),
+            _ => board.get_legal_moves_vec().to_vec(),
+        };
+        if let Some(tt_move) = tt_entry.and_then(|e| e.best_move) {
+            move_to_front(&mut legal_moves, tt_move);
+        }
+        let mut current_alpha = alpha;
+        let mut best_move = None;
+        let mut child_pv = Vec::new();
+        for move_i in legal_moves {
+            child_pv.clear();
+            let mut new_board = board.clone();
+            new_board.do_move(move_i).unwrap();
+            let score = -self.negamax(
+                &mut new_board,
+                depth - 1,
+                -beta,
+                -current_alpha,
+                &mut child_pv,
+                ctx,
+            );
+            if score > current_alpha {
+                current_alpha = score;
+                best_move = Some(move_i);
+                pv.clear();
+                pv.push(Some(move_i));
+                pv.append(&mut child_pv);
+            }
+            if current_alpha >= beta {
+                // cut
+                break;
+            }
+            if ctx.check_abort() {
+                break;
+            }
+        }
+        self.store_result(key, depth, current_alpha, beta, best_move, ctx);
+        current_alpha
+    }
+
+    /// Node one move above the leaves: every child is evaluated in one batch.
+    fn negamax_frontier(
+        &self,
+        board: &mut Board,
+        alpha: i32,
+        pv: &mut Vec<Option<usize>>,
+        ctx: &mut AlphaBetaContext,
+        batch_evaluator: &dyn BatchEvaluator,
+    ) -> (i32, Option<usize>) {
+        let children = board
+            .get_legal_moves_vec()
+            .iter()
+            .map(|&move_i| {
+                let mut new_board = board.clone();
+                new_board.do_move(move_i).unwrap();
+                (move_i, new_board)
+            })
+            .collect::<Vec<_>>();
+        ctx.nodes += children.len() as u64;
+        let leaves = children
+            .iter()
+            .filter(|(_, child)| !child.is_game_over())
+            .map(|(_, child)| child.clone())
+            .collect::<Vec<_>>();
+        let mut scores = batch_evaluator.evaluate_batch(&leaves).into_iter();
+        let mut current_alpha = alpha;
+        let mut best_move = None;
+        for (move_i, child) in &children {
+            let score = if child.is_game_over() {
+                -self.terminal_score(child)
+            } else {
+                -scores.next().unwrap_or(0)
+            };
+            if score > current_alpha {
+                current_alpha = score;
+                best_move = Some(*move_i);
+            }
+        }
+        pv.clear();
+        pv.extend(best_move.map(Some));
+        ctx.check_abort();
+        (current_alpha, best_move)
+    }
+
+    /// Search every root move with children searched to `depth`.
+    fn search_root(
+        &self,
+        board: &mut Board,
+        depth: usize,
+        ctx: &mut AlphaBetaContext,
+    ) -> SearchResult {
+        let mut alpha = i32::MIN + 1;
+        let beta = i32::MAX - 1;
+        let mut pv = Vec::new();
+        if board.is_game_over() {
+            ctx.nodes += 1;
+            alpha = self.terminal_score(board);
+        } else if board.is_pass() {
+            let mut new_board = board.clone();
+            new_board.do_pass().unwrap();
+            let mut child_pv = Vec::new();
+            alpha = -self.negamax(&mut new_board, depth, -beta, -alpha, &mut child_pv, ctx);
+            pv.push(None);
+            pv.append(&mut child_pv);
+        } else {
+            let key = zobrist_hash(board);
+            let mut legal_moves = self.get_legal_moves_ordered(board);
+            if let Some(tt_move) = self.probe_tt(key).and_then(|e| e.best_move) {
+                move_to_front(&mut legal_moves, tt_move);
+            }
+            let n_moves = legal_moves.len();
+            legal_moves.rotate_left(ctx.thread_id % n_moves);
+            let mut child_pv = Vec::new();
+            for move_i in legal_moves {
+                child_pv.clear();
+                let mut new_board = board.clone();
+                new_board.do_move(move_i).unwrap();
+                let score = -self.negamax(&mut new_board, depth, -beta, -alpha, &mut child_pv, ctx);
+                // the score of a move whose search was cut short is meaningless
+                if ctx.aborted {
+                    break;
+                }
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(Some(move_i));
+                    pv.append(&mut child_pv);
+                }
+                if ctx.check_abort() {
+                    break;
+                }
+            }
+            if !ctx.aborted {
+                // children are searched to `depth`, so the root counts one deeper
+                self.store_tt(
+                    key,
+                    TtEntry {
+                        score: alpha,
+                        depth: depth + 1,
+                        bound: Bound::Exact,
+                        best_move: pv.first().copied().flatten(),
+                    },
+                );
+            }
+        }
+        SearchResult {
+            best_move: pv.first().copied().flatten(),
+            score: alpha as f64,
+            pv,
+            depth,
+            ..Default::default()
+        }
+    }
+
+    /// Search the board and report the principal variation and statistics.
+    /// # Note
+    /// * With only `max_depth` as a limit the search goes straight to `max_depth`.
+    ///   A stop request then reports the root moves searched so far.
+    /// * With a timeout the depth is increased iteratively from 0 and the last
+    ///   completed iteration is reported.
+    /// * With a node budget or a progress callback the depth is
+    ///   increased iteratively from 0 up to `max_depth`.
+    /// * If no iteration completes, the best of the root moves searched so far is
+    ///   reported, or the first move in search order if none was searched.
+    /// * Helper threads only fill the transposition table; the result and the
+    ///   completed depth are those of the calling thread, nodes are summed over all threads.
+    pub fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult {
+        let start = Instant::now();
+        self.start_search();
+        let time_keeper = control.time_keeper(self.margin_time);
+        let mut ctx = AlphaBetaContext {
+            time_keeper: time_keeper.as_ref(),
+            max_nodes: control.max_nodes,
+            stop: control.stop,
+            ..Default::default()
+        };
+        let (mut result, helper_nodes) = if self.threads > 1 && self.tt.is_some() {
+            let stop = AtomicBool::new(false);
+            thread::scope(|s| {
+                let helpers = (1..self.threads)
+                    .map(|thread_id| {
+                        let mut board = board.clone();
+                        let stop = &stop;
+                        s.spawn(move || {
+                            let mut ctx = AlphaBetaContext {
+                                stop: Some(stop),
+                                thread_id,
+                                ..Default::default()
+                            };
+                            self.helper_search(&mut board, &mut ctx);
+                            ctx.nodes
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let result = self.main_search(board, control, start, &mut ctx);
+                stop.store(true, Ordering::Relaxed);
+                let helper_nodes = helpers
+                    .into_iter()
+                    .map(|helper| helper.join().unwrap())
+                    .sum::<u64>();
+                (result, helper_nodes)
+            })
+        } else {
+            (self.main_search(board, control, start, &mut ctx), 0)
+        };
+        result.nodes = ctx.nodes + helper_nodes;
+        result.elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+        result.timed_out = ctx.aborted;
+        result
+    }
+
+    fn main_search(
+        &self,
+        board: &mut Board,
+        control: &SearchControl,
+        start: Instant,
+        ctx: &mut AlphaBetaContext,
+    ) -> SearchResult {
+        let depths = match control {
+            // the stop flag is not a limit, searches called from Python always have one
+            SearchControl {
+                timeout: None,
+                max_nodes: None,
+                progress: None,
+                ..
+            } => self.max_depth..self.max_depth + 1,
+            // same depths as rust_reversi_core::search::AlphaBetaSearch::get_move_with_timeout
+            SearchControl {
+                timeout: Some(_), ..
+            } => 0..self.max_depth,
+            _ => 0..self.max_depth + 1,
+        };
+        let mut result = None;
+        for depth in depths {
+            let mut iteration = self.search_root(board, depth, ctx);
+            if ctx.aborted {
+                result.get_or_insert(iteration);
+                break;
+            }
+            iteration.nodes = ctx.nodes;
+            iteration.elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+            control.report(&iteration);
+            result = Some(iteration);
+        }
+        let result = result.unwrap_or_default();
+        if result.best_move.is_some() {
+            return result;
+        }
+        match self.get_legal_moves_ordered(board).first() {
+            Some(&move_i) => {
+                let mut new_board = board.clone();
+                new_board.do_move(move_i).unwrap();
+                SearchResult {
+                    best_move: Some(move_i),
+                    score: -self.score_board(&mut new_board) as f64,
+                    pv: vec![Some(move_i)],
+                    ..Default::default()
+                }
+            }
+            None => result,
+        }
+    }
+
+    /// Iterative deepening until stopped, half of the helpers skipping depth 0
+    /// so that threads work on different depths.
+    fn helper_search(&self, board: &mut Board, ctx: &mut AlphaBetaContext) {
+        for depth in ctx.thread_id % 2..=self.max_depth {
+            self.search_root(board, depth, ctx);
+            if ctx.aborted {
+                break;
+            }
+        }
+    }
+
+    /// Exact score and principal variation of root moves, best first.
+    /// # Arguments
+    /// * `multi_pv` - Number of moves to report. None reports every legal move.
+    /// # Note
+    /// * Moves are searched to the same depth as `search` without a timeout.
+    /// * Returns an empty list if the player must pass or the game is over.
+    /// * If `control` stops the search, only the moves completed so far are reported.
+    pub fn analyze(
+        &self,
+        board: &mut Board,
+        multi_pv: Option<usize>,
+        control: &SearchControl,
+    ) -> Vec<MoveAnalysis> {
+        let time_keeper = control.time_keeper(self.margin_time);
+        let mut ctx = AlphaBetaContext {
+            time_keeper: time_keeper.as_ref(),
+            max_nodes: control.max_nodes,
+            stop: control.stop,
+            ..Default::default()
+        };
+        let beta = i32::MAX - 1;
+        let limit = multi_pv.unwrap_or(usize::MAX);
+        let mut analysis: Vec<(i32, MoveAnalysis)> = Vec::new();
+        if limit == 0 || board.is_game_over() || board.is_pass() {
+            return Vec::new();
+        }
+        self.start_search();
+        for move_i in self.get_legal_moves_ordered(board) {
+            // once the list is full, a move only has to be proven no better than the last entry
+            let lower = if analysis.len() < limit {
+                i32::MIN + 1
+            } else {
+                analysis.last().unwrap().0
+            };
+            let mut new_board = board.clone();
+            new_board.do_move(move_i).unwrap();
+            let mut pv = Vec::new();
+            let score = -self.negamax(
+                &mut new_board,
+                self.max_depth,
+                -beta,
+                -lower,
+                &mut pv,
+                &mut ctx,
+            );
+            if ctx.aborted {
+                break;
+            }
+            if analysis.len() == limit {
+                if score <= lower {
+                    continue;
+                }
+                analysis.pop();
+            }
+            pv.insert(0, Some(move_i));
+            let index = analysis.partition_point(|(s, _)| *s >= score);
+            analysis.insert(
+                index,
+                (
+                    score,
+                    MoveAnalysis {
+                        pos: move_i,
+                        score: score as f64,
+                        pv,
+                        visits: None,
+                    },
+                ),
+            );
+        }
+        analysis.into_iter().map(|(_, a)| a).collect()
+    }
+}
+
+fn move_to_front(moves: &mut [usize], move_i: usize) {
+    if let Some(index) = moves.iter().position(|&m| m == move_i) {
+        moves[..=index].rotate_right(1);
+    }
+}
+
+const DEFAULT_MARGIN_TIME: f64 = 0.005;
+impl Search for AlphaBetaSearch {
+    fn get_move(&self, board: &mut Board) -> Option<usize> {
+        if board.is_pass() {
+            return None;
+        }
+        self.search(board, &SearchControl::default()).best_move
+    }
+
+    fn get_move_with_timeout(&self, board: &mut Board, timeout: Duration) -> Option<usize> {
+        if board.is_pass() {
+            return None;
+        }
+        let control = SearchControl {
+            timeout: Some(timeout),
+            ..Default::default()
+        };
+        self.search(board, &control).best_move
+    }
+
+    fn get_search_score(&self, board: &mut Board) -> f64 {
+        self.search(board, &SearchControl::default()).score
+    }
+}
+
+impl ControlledSearch for AlphaBetaSearch {
+    fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult {
+        AlphaBetaSearch::search(self, board, control)
+    }
+}
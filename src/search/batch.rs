@@ -0,0 +1,15 @@
+use std::fmt::Debug;
+
+use rust_reversi_core::board::Board;
+
+/// Evaluator scoring many boards in one call, e.g. a neural network run on a batch.
+pub trait BatchEvaluator: Send + Sync + Debug {
+    /// Scores of `boards` for their side to move, in the same order.
+    fn evaluate_batch(&self, boards: &[Board]) -> Vec<i32>;
+}
+
+/// Winrate evaluator scoring many boards in one call.
+pub trait BatchWinrateEvaluator: Send + Sync + Debug {
+    /// Winrates of `boards` for their side to move, in the same order.
+    fn evaluate_batch(&self, boards: &[Board]) -> Vec<f64>;
+}
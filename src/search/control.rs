@@ -0,0 +1,46 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use rust_reversi_core::board::Board;
+
+use super::result::SearchResult;
+use super::time_keeper::TimeKeeper;
+
+/// Limits and hooks of one search call, on top of those set at construction.
+#[derive(Clone, Copy, Default)]
+pub struct SearchControl<'a> {
+    pub timeout: Option<Duration>,
+    /// Positions to visit for AlphaBetaSearch and EndgameSolver, playouts for the tree searches.
+    /// Unlike a timeout this gives the same result on any machine.
+    pub max_nodes: Option<u64>,
+    /// Stops the search once set, e.g. from another thread.
+    pub stop: Option<&'a AtomicBool>,
+    /// Called with the result so far after each completed depth of AlphaBetaSearch
+    /// and every `progress_interval` playouts of the tree searches.
+    pub progress: Option<&'a (dyn Fn(&SearchResult) + Sync)>,
+    pub progress_interval: usize,
+}
+
+impl SearchControl<'_> {
+    pub fn time_keeper(&self, margin_time: f64) -> Option<TimeKeeper> {
+        self.timeout.map(|timeout| {
+            let search_duration = (timeout.as_secs_f64() - margin_time).max(0.0);
+            TimeKeeper::new(Duration::from_secs_f64(search_duration))
+        })
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+
+    pub fn report(&self, result: &SearchResult) {
+        if let Some(progress) = self.progress {
+            progress(result);
+        }
+    }
+}
+
+/// Searches that can be run under a `SearchControl`.
+pub trait ControlledSearch: Send + Sync {
+    fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult;
+}
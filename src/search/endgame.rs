@@ -0,0 +1,528 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use rust_reversi_core::board::Board;
+
+use super::control::{ControlledSearch, SearchControl};
+use super::result::SearchResult;
+use super::time_keeper::TimeKeeper;
+use crate::board::neighbors;
+
+const SCORE_MAX: i32 = 64;
+
+// quadrants used for parity ordering
+const QUADRANT_MASKS: [u64; 4] = [
+    0xF0_F0_F0_F0_00_00_00_00,
+    0x0F_0F_0F_0F_00_00_00_00,
+    0x00_00_00_00_F0_F0_F0_F0,
+    0x00_00_00_00_0F_0F_0F_0F,
+];
+const CORNER_MASK: u64 = 0x81_00_00_00_00_00_00_81;
+// one per square, since set_board accepts positions that cannot arise in a game
+const MAX_MOVES: usize = 64;
+
+// below this many empties the search skips the table and orders moves by parity only
+const SHALLOW_EMPTIES: u32 = 7;
+// from this many empties children are looked up in the table before searching
+const ETC_EMPTIES: u32 = 10;
+// the table grows with the number of empties, up to 2^20 entries
+const TABLE_BITS_MIN: u32 = 10;
+const TABLE_BITS_MAX: u32 = 20;
+// the clock and the stop flag are checked every this many positions
+const CHECK_INTERVAL: u64 = 1024;
+
+#[inline]
+fn get_legal_partial(watch: u64, player_board: u64, shift: usize) -> u64 {
+    let mut flip_l = (player_board << shift) & watch;
+    let mut flip_r = (player_board >> shift) & watch;
+    flip_l |= (flip_l << shift) & watch;
+    flip_r |= (flip_r >> shift) & watch;
+    let watch_l = watch & (watch << shift);
+    let watch_r = watch & (watch >> shift);
+    let shift2 = shift + shift;
+    flip_l |= (flip_l << shift2) & watch_l;
+    flip_r |= (flip_r >> shift2) & watch_r;
+    flip_l |= (flip_l << shift2) & watch_l;
+    flip_r |= (flip_r >> shift2) & watch_r;
+    flip_l << shift | flip_r >> shift
+}
+
+#[inline]
+fn legal_moves(player_board: u64, opponent_board: u64) -> u64 {
+    let mask = 0x7E_7E_7E_7E_7E_7E_7E_7E & opponent_board;
+    (get_legal_partial(mask, player_board, 1)
+        | get_legal_partial(opponent_board, player_board, 8)
+        | get_legal_partial(mask, player_board, 9)
+        | get_legal_partial(mask, player_board, 7))
+        & !(player_board | opponent_board)
+}
+
+#[inline]
+fn flips(player_board: u64, opponent_board: u64, pos: u64) -> u64 {
+    let mut reversed: u64 = 0;
+    macro_rules! get_reverse {
+        ($mask:expr, $shift:ident, $dir:expr) => {
+            let mut mask = $mask & pos.$shift($dir);
+            let mut tmp = 0;
+            while mask & opponent_board != 0 {
+                tmp |= mask;
+                mask = $mask & mask.$shift($dir);
+            }
+            if mask & player_board != 0 {
+                reversed |= tmp;
+            }
+        };
+    }
+    get_reverse!(0xFE_FE_FE_FE_FE_FE_FE_FE, wrapping_shl, 1);
+    get_reverse!(0xFF_FF_FF_FF_FF_FF_FF_00, wrapping_shl, 8);
+    get_reverse!(0xFE_FE_FE_FE_FE_FE_FE_00, wrapping_shl, 9);
+    get_reverse!(0x7F_7F_7F_7F_7F_7F_7F_00, wrapping_shl, 7);
+    get_reverse!(0x7F_7F_7F_7F_7F_7F_7F_7F, wrapping_shr, 1);
+    get_reverse!(0x00_FF_FF_FF_FF_FF_FF_FF, wrapping_shr, 8);
+    get_reverse!(0x00_7F_7F_7F_7F_7F_7F_7F, wrapping_shr, 9);
+    get_reverse!(0x00_FE_FE_FE_FE_FE_FE_FE, wrapping_shr, 7);
+    reversed
+}
+
+/// Final disc differential for the player. Empty squares go to the winner.
+#[inline]
+fn final_score(player_board: u64, opponent_board: u64) -> i32 {
+    let player = player_board.count_ones() as i32;
+    let opponent = opponent_board.count_ones() as i32;
+    let empties = 64 - player - opponent;
+    match player.cmp(&opponent) {
+        std::cmp::Ordering::Greater => player - opponent + empties,
+        std::cmp::Ordering::Less => player - opponent - empties,
+        std::cmp::Ordering::Equal => 0,
+    }
+}
+
+/// Empty squares in quadrants holding an odd number of empties.
+#[inline]
+fn odd_quadrants(empties: u64) -> u64 {
+    let mut odd = 0;
+    for mask in QUADRANT_MASKS {
+        if (empties & mask).count_ones() % 2 == 1 {
+            odd |= mask;
+        }
+    }
+    empties & odd
+}
+
+#[inline]
+fn bit_to_pos(bit: u64) -> usize {
+    bit.leading_zeros() as usize
+}
+
+/// What the solver computes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveMode {
+    /// Exact final disc differential.
+    Exact,
+    /// Only the sign of the final disc differential: 1 win, 0 draw, -1 loss.
+    WinLossDraw,
+}
+
+/// Result of an endgame solve from the side to move's point of view.
+#[derive(Debug, Clone)]
+pub struct EndgameResult {
+    /// Disc differential under `SolveMode::Exact`, -1/0/1 under `SolveMode::WinLossDraw`.
+    pub score: i32,
+    /// Every move that reaches `score`. Empty if the player must pass or the game is over.
+    pub best_moves: Vec<usize>,
+    /// Positions searched, except the last few plies.
+    pub nodes: u64,
+}
+
+struct MoveList {
+    moves: [(u64, u64, i32); MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    /// Legal moves in fastest-first order: fewest opponent replies first, with
+    /// corners and odd quadrants as tie-breakers.
+    fn new(player_board: u64, opponent_board: u64, legal: u64) -> Self {
+        let empties = !(player_board | opponent_board);
+        let odd_quadrants = odd_quadrants(empties);
+        let mut list = MoveList {
+            moves: [(0, 0, 0); MAX_MOVES],
+            len: 0,
+        };
+        let mut rest = legal;
+        while rest != 0 {
+            let bit = rest & rest.wrapping_neg();
+            rest ^= bit;
+            let flipped = flips(player_board, opponent_board, bit);
+            let next_player = opponent_board ^ flipped;
+            let next_opponent = player_board | flipped | bit;
+            let replies = legal_moves(next_player, next_opponent);
+            // lower keys are searched first; opponent corner replies count twice
+            let mut key = (replies.count_ones() + (replies & CORNER_MASK).count_ones()) as i32 * 16;
+            key += (neighbors(next_opponent) & empties & !bit).count_ones() as i32;
+            if bit & CORNER_MASK != 0 {
+                key -= 32;
+            }
+            if odd_quadrants & bit == 0 {
+                key += 4;
+            }
+            // insertion sort, lists are short
+            let mut i = list.len;
+            while i > 0 && list.moves[i - 1].2 > key {
+                list.moves[i] = list.moves[i - 1];
+                i -= 1;
+            }
+            list.moves[i] = (bit, flipped, key);
+            list.len += 1;
+        }
+        list
+    }
+
+    fn move_to_front(&mut self, bit: u64) {
+        if let Some(i) = self.moves[..self.len].iter().position(|m| m.0 == bit) {
+            self.moves[..=i].rotate_right(1);
+        }
+    }
+}
+
+fn solve_last1(player_board: u64, opponent_board: u64) -> i32 {
+    let empty = !(player_board | opponent_board);
+    let flipped = flips(player_board, opponent_board, empty);
+    // the board is full after the last move, so the score is 2 * discs - 64
+    if flipped != 0 {
+        let player = (player_board | flipped).count_ones() as i32 + 1;
+        return 2 * player - 64;
+    }
+    let flipped = flips(opponent_board, player_board, empty);
+    if flipped != 0 {
+        let player = (player_board ^ flipped).count_ones() as i32;
+        return 2 * player - 64;
+    }
+    final_score(player_board, opponent_board)
+}
+
+/// Search for the last few empties: no table, moves tried straight from the
+/// empty squares with odd quadrants first.
+fn negamax_shallow(player_board: u64, opponent_board: u64, mut alpha: i32, beta: i32) -> i32 {
+    let empties = !(player_board | opponent_board);
+    if empties.count_ones() == 1 {
+        return solve_last1(player_board, opponent_board);
+    }
+    let odd = odd_quadrants(empties);
+    let mut best = -SCORE_MAX - 1;
+    for candidates in [odd, empties & !odd] {
+        let mut rest = candidates;
+        while rest != 0 {
+            let bit = rest & rest.wrapping_neg();
+            rest ^= bit;
+            let flipped = flips(player_board, opponent_board, bit);
+            if flipped == 0 {
+                continue;
+            }
+            let score = -negamax_shallow(
+                opponent_board ^ flipped,
+                player_board | flipped | bit,
+                -beta,
+                -alpha,
+            );
+            if score > best {
+                best = score;
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        return best;
+                    }
+                }
+            }
+        }
+    }
+    if best == -SCORE_MAX - 1 {
+        // no legal move
+        if legal_moves(opponent_board, player_board) == 0 {
+            return final_score(player_board, opponent_board);
+        }
+        return -negamax_shallow(opponent_board, player_board, -beta, -alpha);
+    }
+    best
+}
+
+#[derive(Clone, Copy, Default)]
+struct TableEntry {
+    player_board: u64,
+    opponent_board: u64,
+    lower: i8,
+    upper: i8,
+    n_empties: u8,
+    best_move: u64,
+}
+
+/// Bounds of already solved positions and limits, kept for one solve.
+struct EndgameContext<'a> {
+    table: Vec<TableEntry>,
+    table_bits: u32,
+    nodes: u64,
+    time_keeper: Option<TimeKeeper>,
+    max_nodes: Option<u64>,
+    stop: Option<&'a AtomicBool>,
+    aborted: bool,
+}
+
+impl<'a> EndgameContext<'a> {
+    fn new(n_empties: u32, control: &SearchControl<'a>) -> Self {
+        let table_bits = n_empties.clamp(TABLE_BITS_MIN, TABLE_BITS_MAX);
+        Self {
+            table: vec![TableEntry::default(); 1 << table_bits],
+            table_bits,
+            nodes: 0,
+            time_keeper: control.time_keeper(0.0),
+            max_nodes: control.max_nodes,
+            stop: control.stop,
+            aborted: false,
+        }
+    }
+
+    /// Whether the solve must stop. Scores of an aborted solve are meaningless.
+    fn check_abort(&mut self) -> bool {
+        if self
+            .max_nodes
+            .is_some_and(|max_nodes| self.nodes >= max_nodes)
+        {
+            self.aborted = true;
+        }
+        if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            if self
+                .time_keeper
+                .as_ref()
+                .is_some_and(|time_keeper| time_keeper.is_timeout())
+            {
+                self.aborted = true;
+            }
+            if self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed)) {
+                self.aborted = true;
+            }
+        }
+        self.aborted
+    }
+
+    /// Exact score by bisecting the score range with null window searches.
+    fn solve_exact(&mut self, player_board: u64, opponent_board: u64) -> i32 {
+        let mut lower = -SCORE_MAX;
+        let mut upper = SCORE_MAX;
+        while lower < upper && !self.aborted {
+            let guess = (lower + upper).div_euclid(2);
+            let score = self.negamax(player_board, opponent_board, guess, guess + 1);
+            if score > guess {
+                lower = score;
+            } else {
+                upper = score;
+            }
+        }
+        lower
+    }
+
+    #[inline]
+    fn index(&self, player_board: u64, opponent_board: u64) -> usize {
+        let h = (player_board ^ opponent_board.rotate_left(23)).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        (h >> (64 - self.table_bits)) as usize
+    }
+
+    fn negamax(
+        &mut self,
+        player_board: u64,
+        opponent_board: u64,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        if self.aborted || self.check_abort() {
+            return 0;
+        }
+        self.nodes += 1;
+        let n_empties = (!(player_board | opponent_board)).count_ones();
+        if n_empties < SHALLOW_EMPTIES {
+            return negamax_shallow(player_board, opponent_board, alpha, beta);
+        }
+        let legal = legal_moves(player_board, opponent_board);
+        if legal == 0 {
+            if legal_moves(opponent_board, player_board) == 0 {
+                return final_score(player_board, opponent_board);
+            }
+            return -self.negamax(opponent_board, player_board, -beta, -alpha);
+        }
+
+        let index = self.index(player_board, opponent_board);
+        let entry = self.table[index];
+        let mut hash_move = 0;
+        if entry.player_board == player_board && entry.opponent_board == opponent_board {
+            let (lower, upper) = (entry.lower as i32, entry.upper as i32);
+            if lower >= beta {
+                return lower;
+            }
+            if upper <= alpha || lower == upper {
+                return upper;
+            }
+            alpha = alpha.max(lower);
+            beta = beta.min(upper);
+            hash_move = entry.best_move;
+        }
+
+        let original_alpha = alpha;
+        let mut list = MoveList::new(player_board, opponent_board, legal);
+        if n_empties >= ETC_EMPTIES {
+            // enhanced transposition cutoff: a child already refuted in the table
+            for &(bit, flipped, _) in &list.moves[..list.len] {
+                let next_player = opponent_board ^ flipped;
+                let next_opponent = player_board | flipped | bit;
+                let child = self.table[self.index(next_player, next_opponent)];
+                if child.player_board == next_player
+                    && child.opponent_board == next_opponent
+                    && -(child.upper as i32) >= beta
+                {
+                    return -(child.upper as i32);
+                }
+            }
+        }
+        list.move_to_front(hash_move);
+        let mut best = -SCORE_MAX - 1;
+        let mut best_move = 0;
+        for (i, &(bit, flipped, _)) in list.moves[..list.len].iter().enumerate() {
+            let next_player = opponent_board ^ flipped;
+            let next_opponent = player_board | flipped | bit;
+            // principal variation search: later moves only need to be proven worse
+            let score = if i == 0 {
+                -self.negamax(next_player, next_opponent, -beta, -alpha)
+            } else {
+                let score = -self.negamax(next_player, next_opponent, -alpha - 1, -alpha);
+                if alpha < score && score < beta {
+                    -self.negamax(next_player, next_opponent, -beta, -score)
+                } else {
+                    score
+                }
+            };
+            if score > best {
+                best = score;
+                best_move = bit;
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+        if self.aborted {
+            return best;
+        }
+
+        let (lower, upper) = if best <= original_alpha {
+            (-SCORE_MAX, best)
+        } else if best >= beta {
+            (best, SCORE_MAX)
+        } else {
+            (best, best)
+        };
+        // keep the deeper of two different positions
+        let same = entry.player_board == player_board && entry.opponent_board == opponent_board;
+        if same || entry.n_empties as u32 <= n_empties {
+            self.table[index] = TableEntry {
+                player_board,
+                opponent_board,
+                lower: lower as i8,
+                upper: upper as i8,
+                n_empties: n_empties as u8,
+                best_move,
+            };
+        }
+        best
+    }
+}
+
+/// Exact endgame solver.
+/// # Note
+/// * Scores count empty squares for the winner, as in tournament play.
+/// * Search time grows exponentially with the number of empty squares.
+///   Around 20 empties is practical.
+#[derive(Debug, Clone, Default)]
+pub struct EndgameSolver;
+
+impl EndgameSolver {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Solve the board for the side to move.
+    /// # Note
+    /// * `best_moves` lists every move reaching the score, in ascending order.
+    /// * Returns None if `control` stops the solve before it finishes.
+    pub fn solve(
+        &self,
+        board: &Board,
+        mode: SolveMode,
+        control: &SearchControl,
+    ) -> Option<EndgameResult> {
+        let (player_board, opponent_board, _) = board.get_board();
+        let n_empties = (!(player_board | opponent_board)).count_ones();
+        let mut ctx = EndgameContext::new(n_empties, control);
+        let score = match mode {
+            SolveMode::Exact => ctx.solve_exact(player_board, opponent_board),
+            SolveMode::WinLossDraw => ctx.negamax(player_board, opponent_board, -1, 1).signum(),
+        };
+        // raw score a move has to reach to be one of the best moves
+        let threshold = match (mode, score) {
+            (SolveMode::Exact, _) => score,
+            (SolveMode::WinLossDraw, -1) => -SCORE_MAX,
+            (SolveMode::WinLossDraw, _) => score,
+        };
+        let mut best_moves = Vec::new();
+        let mut legal = legal_moves(player_board, opponent_board);
+        while legal != 0 {
+            let bit = legal & legal.wrapping_neg();
+            legal ^= bit;
+            let flipped = flips(player_board, opponent_board, bit);
+            let next_player = opponent_board ^ flipped;
+            let next_opponent = player_board | flipped | bit;
+            // null window test: no move beats the root score, so reaching it means a tie
+            if -ctx.negamax(next_player, next_opponent, -threshold, -threshold + 1) >= threshold {
+                best_moves.push(bit_to_pos(bit));
+            }
+        }
+        if ctx.aborted {
+            return None;
+        }
+        best_moves.sort_unstable();
+        Some(EndgameResult {
+            score,
+            best_moves,
+            nodes: ctx.nodes,
+        })
+    }
+}
+
+impl ControlledSearch for EndgameSolver {
+    /// Exact solve. A stopped solve reports no move and `timed_out`.
+    fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult {
+        let start = Instant::now();
+        let result = self.solve(board, SolveMode::Exact, control);
+        let (player_board, opponent_board, _) = board.get_board();
+        let n_empties = (!(player_board | opponent_board)).count_ones() as usize;
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+        match result {
+            Some(result) => {
+                let best_move = result.best_moves.first().copied();
+                SearchResult {
+                    best_move,
+                    score: result.score as f64,
+                    pv: best_move.map(Some).into_iter().collect(),
+                    depth: n_empties,
+                    nodes: result.nodes,
+                    elapsed_ms,
+                    ..Default::default()
+                }
+            }
+            None => SearchResult {
+                elapsed_ms,
+                timed_out: true,
+                ..Default::default()
+            },
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use rust_reversi_core::board::Board as RustBoard;
+
+use super::control::{ControlledSearch, SearchControl};
+use super::result::SearchResult;
+
+/// Stop flags of the searches currently running on one search instance,
+/// and the first exception raised by its Python evaluator.
+#[derive(Debug, Default)]
+pub struct RunningSearches {
+    flags: Mutex<Vec<Arc<AtomicBool>>>,
+    failure: Mutex<Option<Arc<PyErr>>>,
+}
+
+impl RunningSearches {
+    pub fn stop_all(&self) {
+        for flag in self.flags.lock().unwrap().iter() {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Keep the first exception and stop every running search.
+    pub fn fail(&self, err: PyErr) {
+        self.failure
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| Arc::new(err));
+        self.stop_all();
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.failure.lock().unwrap().is_some()
+    }
+
+    pub fn take_failure(&self) -> Option<PyErr> {
+        let failure = self.failure.lock().unwrap().take()?;
+        Some(Python::with_gil(|py| failure.clone_ref(py)))
+    }
+
+    /// Run `f` with `stop` registered. Returns the exception raised meanwhile, if any.
+    /// # Note
+    /// * Every search running when the exception was raised returns it;
+    ///   the last one to finish clears it.
+    fn run<T>(&self, stop: &Arc<AtomicBool>, f: impl FnOnce() -> T) -> (T, Option<Arc<PyErr>>) {
+        self.flags.lock().unwrap().push(stop.clone());
+        let result = f();
+        let mut flags = self.flags.lock().unwrap();
+        flags.retain(|flag| !Arc::ptr_eq(flag, stop));
+        let mut failure = self.failure.lock().unwrap();
+        let failure = if flags.is_empty() {
+            failure.take()
+        } else {
+            failure.clone()
+        };
+        (result, failure)
+    }
+}
+
+/// Per-call options of searches called from Python.
+#[derive(Default)]
+pub struct SearchOptions {
+    pub timeout_ms: Option<u64>,
+    pub max_nodes: Option<u64>,
+    pub callback: Option<Py<PyAny>>,
+    pub callback_interval: usize,
+}
+
+/// Run `f` under a control built from `options`, without the GIL.
+/// # Note
+/// * The first exception raised by the callback stops the search and is returned,
+///   as is an exception raised by the evaluator.
+fn run_controlled<S, T>(
+    search: &S,
+    running: &RunningSearches,
+    board: &mut RustBoard,
+    options: &SearchOptions,
+    stop: &Arc<AtomicBool>,
+    f: impl FnOnce(&S, &mut RustBoard, &SearchControl) -> T,
+) -> PyResult<T>
+where
+    S: ControlledSearch + ?Sized,
+{
+    let error = Mutex::new(None);
+    let progress = |result: &SearchResult| {
+        let Some(callback) = &options.callback else {
+            return;
+        };
+        Python::with_gil(|py| {
+            if let Err(err) = callback.call1(py, (result.clone(),)) {
+                error.lock().unwrap().get_or_insert(err);
+                stop.store(true, Ordering::Relaxed);
+            }
+        });
+    };
+    let control = SearchControl {
+        timeout: options.timeout_ms.map(Duration::from_millis),
+        max_nodes: options.max_nodes,
+        stop: Some(stop),
+        progress: options.callback.as_ref().map(|_| &progress as _),
+        progress_interval: options.callback_interval,
+    };
+    let (result, failure) = running.run(stop, || f(search, board, &control));
+    if let Some(err) = error.into_inner().unwrap() {
+        return Err(err);
+    }
+    match failure {
+        Some(failure) => Err(Python::with_gil(|py| failure.clone_ref(py))),
+        None => Ok(result),
+    }
+}
+
+/// Run a search on the calling thread with the GIL released.
+pub fn run_search<S, T>(
+    py: Python<'_>,
+    search: &S,
+    running: &RunningSearches,
+    board: &mut RustBoard,
+    options: SearchOptions,
+    f: impl FnOnce(&S, &mut RustBoard, &SearchControl) -> T + Send,
+) -> PyResult<T>
+where
+    S: ControlledSearch + ?Sized,
+    T: Send,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    py.allow_threads(|| run_controlled(search, running, board, &options, &stop, f))
+}
+
+/// Run a search on a new thread.
+pub fn start_search<S>(
+    search: Arc<S>,
+    running: Arc<RunningSearches>,
+    mut board: RustBoard,
+    options: SearchOptions,
+) -> SearchHandle
+where
+    S: ControlledSearch + ?Sized + 'static,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let thread = thread::spawn(move || {
+        run_controlled(
+            &*search,
+            &running,
+            &mut board,
+            &options,
+            &thread_stop,
+            |search, board, control| search.search(board, control),
+        )
+    });
+    SearchHandle {
+        thread: Some(thread),
+        outcome: None,
+        stop,
+    }
+}
+
+/// Search running in the background, returned by `start_search`.
+#[pyclass(module = "rust_reversi")]
+pub struct SearchHandle {
+    thread: Option<JoinHandle<PyResult<SearchResult>>>,
+    outcome: Option<PyResult<SearchResult>>,
+    stop: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl SearchHandle {
+    fn is_done(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_none_or(|thread| thread.is_finished())
+    }
+
+    /// Stop the search. `result` then returns the result so far.
+    fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Wait for the search to finish and return its result.
+    fn result(&mut self, py: Python<'_>) -> PyResult<SearchResult> {
+        if let Some(thread) = self.thread.take() {
+            let outcome = py
+                .allow_threads(|| thread.join())
+                .unwrap_or_else(|_| Err(PyRuntimeError::new_err("Search thread panicked")));
+            self.outcome = Some(outcome);
+        }
+        match self.outcome.as_ref().unwrap() {
+            Ok(result) => Ok(result.clone()),
+            Err(err) => Err(err.clone_ref(py)),
+        }
+    }
+}
@@ -0,0 +1,563 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_reversi_core::board::Board;
+use rust_reversi_core::search::Search;
+
+use super::control::{ControlledSearch, SearchControl};
+use super::result::{MoveAnalysis, SearchPolicy, SearchResult};
+use super::rollout::RolloutPolicy;
+
+/// Value of a finished game for the side to move.
+fn terminal_score(board: &Board) -> f64 {
+    match (board.is_win(), board.is_lose()) {
+        (Ok(true), _) => 1.0,
+        (_, Ok(true)) => 0.0,
+        _ => 0.5,
+    }
+}
+
+struct MctsNode {
+    board: Board,
+    w: f64,
+    n_visits: usize,
+    children: Option<Vec<MctsNode>>,
+}
+
+// a kept tree is too large to print node by node
+impl std::fmt::Debug for MctsNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MctsNode")
+            .field("w", &self.w)
+            .field("n_visits", &self.n_visits)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MctsNode {
+    fn new(board: Board) -> Self {
+        Self {
+            board,
+            w: 0.0,
+            n_visits: 0,
+            children: None,
+        }
+    }
+
+    /// Mean value for the side to move, 0.5 before the first visit.
+    fn winrate(&self) -> f64 {
+        if self.n_visits > 0 {
+            self.w / self.n_visits as f64
+        } else {
+            0.5
+        }
+    }
+
+    fn expand(&mut self) {
+        if self.children.is_some() {
+            panic!("MctsNode::expand called on a node that is already expanded.");
+        }
+        if self.board.is_game_over() {
+            panic!("MctsNode::expand called on a node that is a terminal node.");
+        }
+        if let Some(children) = self.board.get_child_boards() {
+            self.children = Some(children.into_iter().map(MctsNode::new).collect());
+        } else {
+            let mut board = self.board.clone();
+            board.do_pass().unwrap();
+            self.children = Some(vec![MctsNode::new(board)]);
+        }
+    }
+
+    fn select_child_index(&self, ctx: &MctsContext) -> usize {
+        let children = self.children.as_ref().unwrap();
+        for (i, child) in children.iter().enumerate() {
+            if child.n_visits == 0 {
+                return i;
+            }
+        }
+        let mut t: f64 = 0.0;
+        for child in children {
+            t += child.n_visits as f64;
+        }
+        let mut best_child_index = 0;
+        let mut best_ucb = f64::NEG_INFINITY;
+        for (i, child) in children.iter().enumerate() {
+            let ucb = 1.0 - child.w / child.n_visits as f64
+                + ctx.c * (2.0 * t.ln() / child.n_visits as f64).sqrt();
+            if ucb > best_ucb {
+                best_ucb = ucb;
+                best_child_index = i;
+            }
+        }
+        best_child_index
+    }
+
+    fn evaluate(&mut self, ctx: &mut MctsContext) -> f64 {
+        if self.board.is_game_over() {
+            let value = match self.board.get_winner().unwrap() {
+                Some(winner) => {
+                    if winner == self.board.get_turn() {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                }
+                None => 0.5,
+            };
+            self.w += value;
+            self.n_visits += 1;
+            value
+        } else if self.children.is_none() {
+            let value = ctx.rollout.play_out(&self.board, &mut ctx.rng);
+            self.w += value;
+            self.n_visits += 1;
+
+            if self.n_visits >= ctx.expansion_threshold {
+                self.expand();
+            }
+
+            value
+        } else {
+            let child_index = self.select_child_index(ctx);
+            let value = 1.0 - self.children.as_mut().unwrap()[child_index].evaluate(ctx);
+            self.w += value;
+            self.n_visits += 1;
+            value
+        }
+    }
+
+    fn best_child_index(&self) -> usize {
+        let mut best_child_index = 0;
+        let mut best_n_visits = 0;
+        for (i, child) in self.children.as_ref().unwrap().iter().enumerate() {
+            if child.n_visits > best_n_visits {
+                best_n_visits = child.n_visits;
+                best_child_index = i;
+            }
+        }
+        best_child_index
+    }
+
+    /// Move leading to the child at `index`. None for the pass child.
+    fn child_move(&self, index: usize) -> Option<usize> {
+        let mut board = self.board.clone();
+        if board.is_pass() {
+            None
+        } else {
+            Some(board.get_legal_moves_vec()[index])
+        }
+    }
+
+    /// Visits and winrates of the children.
+    fn policy(&self, nodes: u64) -> SearchPolicy {
+        let children = self.children.as_ref().unwrap().iter().enumerate();
+        SearchPolicy::from_children(
+            children.map(|(i, child)| {
+                let n_visits = child.n_visits as f64;
+                (
+                    self.child_move(i),
+                    child.n_visits as u64,
+                    n_visits - child.w,
+                )
+            }),
+            nodes,
+        )
+    }
+
+    /// Most visited line from this node.
+    fn pv(&self) -> Vec<Option<usize>> {
+        let mut pv = Vec::new();
+        let mut node = self;
+        while let Some(children) = &node.children {
+            let index = node.best_child_index();
+            if children[index].n_visits == 0 {
+                break;
+            }
+            pv.push(node.child_move(index));
+            node = &children[index];
+        }
+        pv
+    }
+
+    /// Add the statistics of `other`, a tree of the same board searched by another thread.
+    fn merge(&mut self, other: MctsNode) {
+        self.w += other.w;
+        self.n_visits += other.n_visits;
+        match (&mut self.children, other.children) {
+            (Some(children), Some(other_children)) => {
+                for (child, other_child) in children.iter_mut().zip(other_children) {
+                    child.merge(other_child);
+                }
+            }
+            (children @ None, other_children) => *children = other_children,
+            _ => {}
+        }
+    }
+
+    /// Subtree whose board is `board`, at most `max_plies` plies below this node.
+    fn take_subtree(self, board: &Board, max_plies: usize) -> Option<MctsNode> {
+        // not `==`, which also compares the legal move cache
+        if self.board.get_board() == board.get_board() {
+            return Some(self);
+        }
+        if max_plies == 0 {
+            return None;
+        }
+        self.children?
+            .into_iter()
+            .find_map(|child| child.take_subtree(board, max_plies - 1))
+    }
+
+    /// Deepest visited ply below this node.
+    fn depth(&self) -> usize {
+        match &self.children {
+            Some(children) => children
+                .iter()
+                .filter(|child| child.n_visits > 0)
+                .map(|child| child.depth() + 1)
+                .max()
+                .unwrap_or(0),
+            None => 0,
+        }
+    }
+}
+
+/// State shared by every node during one search.
+struct MctsContext {
+    c: f64,
+    expansion_threshold: usize,
+    rollout: RolloutPolicy,
+    rng: StdRng,
+}
+
+/// The Monte Carlo Tree Search Search.
+/// # Note
+/// * Same algorithm as `rust_reversi_core::search::MctsSearch`, with a seedable RNG.
+/// * With tree reuse, the tree of the last search is kept and searched further
+///   if the next board is reached from it within `MAX_REUSE_PLIES` plies.
+/// * With more than one thread, each thread grows its own tree with its share of
+///   the playouts and the trees are merged (root parallelization).
+/// * Playouts are uniformly random unless another rollout policy is set.
+#[derive(Debug)]
+pub struct MctsSearch {
+    n_playouts: usize,
+    c: f64,
+    expansion_threshold: usize,
+    rollout: RolloutPolicy,
+    threads: usize,
+    margin_time: f64,
+    check_interval: usize,
+    rng: Mutex<StdRng>,
+    reuse_tree: AtomicBool,
+    tree: Mutex<Option<MctsNode>>,
+}
+
+impl MctsSearch {
+    /// Create a new MctsSearch instance.
+    /// # Arguments
+    /// * `n_playouts` - The number of playouts to run.
+    /// * `c` - The exploration parameter.
+    /// * `expansion_threshold` - The number of visits to expand the node.
+    /// * `seed` - Seed of the RNG. None seeds from the OS.
+    pub fn new(n_playouts: usize, c: f64, expansion_threshold: usize, seed: Option<u64>) -> Self {
+        Self {
+            n_playouts,
+            c,
+            expansion_threshold,
+            rollout: RolloutPolicy::default(),
+            threads: 1,
+            margin_time: DEFAULT_MARGIN_TIME,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            rng: Mutex::new(match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            }),
+            reuse_tree: AtomicBool::new(false),
+            tree: Mutex::new(None),
+        }
+    }
+
+    /// Choose playout moves and when to stop playouts with `rollout`.
+    pub fn set_rollout_policy(&mut self, rollout: RolloutPolicy) {
+        self.rollout = rollout;
+    }
+
+    /// Set the number of search threads.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    pub fn reuse_tree(&self) -> bool {
+        self.reuse_tree.load(Ordering::Relaxed)
+    }
+
+    /// Keep the tree from one call to the next, e.g. while playing one game.
+    pub fn set_reuse_tree(&self, reuse_tree: bool) {
+        self.reuse_tree.store(reuse_tree, Ordering::Relaxed);
+        if !reuse_tree {
+            self.clear_tree();
+        }
+    }
+
+    pub fn clear_tree(&self) {
+        *self.tree.lock().unwrap() = None;
+    }
+
+    /// Root for `board`: the matching part of the kept tree, or a new node.
+    fn take_root(&self, board: &Board) -> MctsNode {
+        let tree = self.tree.lock().unwrap().take();
+        let root = tree
+            .filter(|_| self.reuse_tree())
+            .and_then(|tree| tree.take_subtree(board, MAX_REUSE_PLIES));
+        let mut root = root.unwrap_or_else(|| MctsNode::new(board.clone()));
+        if root.children.is_none() {
+            root.expand();
+        }
+        root
+    }
+
+    /// Keep `root` for the next search if tree reuse is on.
+    fn keep_root(&self, root: MctsNode) {
+        if self.reuse_tree() {
+            *self.tree.lock().unwrap() = Some(root);
+        }
+    }
+
+    fn new_context(&self) -> MctsContext {
+        // each search draws its own seed, so a seeded instance gives the same sequence of moves
+        let seed = self.rng.lock().unwrap().gen();
+        MctsContext {
+            c: self.c,
+            expansion_threshold: self.expansion_threshold,
+            rollout: self.rollout.clone(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Expand the root and run the playouts, stopping early on timeout or stop.
+    /// Returns the root, the number of playouts run and the number of playouts planned.
+    /// # Note
+    /// * `control.max_nodes` overrides the number of playouts set at construction.
+    /// * A reused root keeps its visits, so the result covers more playouts than were run.
+    /// * With more than one thread, progress is reported for the tree of the calling thread.
+    fn run(
+        &self,
+        board: &Board,
+        control: &SearchControl,
+        start: Instant,
+    ) -> (MctsNode, usize, usize) {
+        let mut root = self.take_root(board);
+        let n_playouts = control.max_nodes.map_or(self.n_playouts, |n| n as usize);
+        // contexts are drawn in order, so a seeded instance stays reproducible
+        let mut contexts = (0..self.threads)
+            .map(|_| self.new_context())
+            .collect::<Vec<_>>();
+        let share =
+            |i: usize| n_playouts / self.threads + usize::from(i < n_playouts % self.threads);
+        let mut ctx = contexts.remove(0);
+        if contexts.is_empty() {
+            let playouts = self.run_playouts(&mut root, &mut ctx, control, n_playouts, start);
+            return (root, playouts, n_playouts);
+        }
+        let helper_control = SearchControl {
+            progress: None,
+            ..*control
+        };
+        let playouts = thread::scope(|s| {
+            let helpers = contexts
+                .into_iter()
+                .enumerate()
+                .map(|(i, mut ctx)| {
+                    let helper_control = &helper_control;
+                    s.spawn(move || {
+                        let mut root = MctsNode::new(board.clone());
+                        root.expand();
+                        let playouts = self.run_playouts(
+                            &mut root,
+                            &mut ctx,
+                            helper_control,
+                            share(i + 1),
+                            start,
+                        );
+                        (root, playouts)
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut playouts = self.run_playouts(&mut root, &mut ctx, control, share(0), start);
+            for helper in helpers {
+                let (tree, helper_playouts) = helper.join().unwrap();
+                root.merge(tree);
+                playouts += helper_playouts;
+            }
+            playouts
+        });
+        (root, playouts, n_playouts)
+    }
+
+    /// Run `n_playouts` playouts from `root`, stopping early on timeout or stop.
+    /// Returns the number of playouts run.
+    fn run_playouts(
+        &self,
+        root: &mut MctsNode,
+        ctx: &mut MctsContext,
+        control: &SearchControl,
+        n_playouts: usize,
+        start: Instant,
+    ) -> usize {
+        let time_keeper = control.time_keeper(self.margin_time);
+        let mut playouts = 0;
+        while playouts < n_playouts {
+            root.evaluate(ctx);
+            playouts += 1;
+            if control.progress_interval > 0 && playouts % control.progress_interval == 0 {
+                control.report(&self.make_result(root, playouts, n_playouts, start));
+            }
+            if (playouts - 1) % self.check_interval == 0 {
+                let timed_out = time_keeper.as_ref().is_some_and(|t| t.is_timeout());
+                if timed_out || control.is_stopped() {
+                    break;
+                }
+            }
+        }
+        playouts
+    }
+
+    fn make_result(
+        &self,
+        root: &MctsNode,
+        playouts: usize,
+        n_playouts: usize,
+        start: Instant,
+    ) -> SearchResult {
+        let index = root.best_child_index();
+        let best_child = &root.children.as_ref().unwrap()[index];
+        SearchResult {
+            best_move: root.child_move(index),
+            score: 1.0 - best_child.winrate(),
+            pv: root.pv(),
+            depth: root.depth(),
+            nodes: playouts as u64,
+            elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+            timed_out: playouts < n_playouts,
+        }
+    }
+
+    /// Search the board and report the principal variation and statistics.
+    pub fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult {
+        let start = Instant::now();
+        if board.is_game_over() {
+            return SearchResult {
+                score: terminal_score(board),
+                elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+                ..Default::default()
+            };
+        }
+        let (root, playouts, n_playouts) = self.run(board, control, start);
+        let result = self.make_result(&root, playouts, n_playouts, start);
+        self.keep_root(root);
+        result
+    }
+
+    /// Winrate of the root for the side to move.
+    pub fn search_score(&self, board: &mut Board, control: &SearchControl) -> f64 {
+        if board.is_game_over() {
+            return terminal_score(board);
+        }
+        let (root, ..) = self.run(board, control, Instant::now());
+        let score = root.winrate();
+        self.keep_root(root);
+        score
+    }
+
+    /// Visits and winrates of the root moves.
+    /// # Note
+    /// * Visits and winrates are empty if the game is over.
+    pub fn search_policy(&self, board: &mut Board, control: &SearchControl) -> SearchPolicy {
+        if board.is_game_over() {
+            return SearchPolicy::from_children([], 0);
+        }
+        let (root, playouts, _) = self.run(board, control, Instant::now());
+        let policy = root.policy(playouts as u64);
+        self.keep_root(root);
+        policy
+    }
+
+    /// Visit count, Q-value and principal variation of root moves, most visited first.
+    /// # Arguments
+    /// * `multi_pv` - Number of moves to report. None reports every legal move.
+    /// # Note
+    /// * The score is the winrate of the move for the side to move.
+    /// * Returns an empty list if the player must pass or the game is over.
+    pub fn analyze(
+        &self,
+        board: &mut Board,
+        multi_pv: Option<usize>,
+        control: &SearchControl,
+    ) -> Vec<MoveAnalysis> {
+        if board.is_game_over() || board.is_pass() {
+            return Vec::new();
+        }
+        let (root, ..) = self.run(board, control, Instant::now());
+        let mut analysis = root
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(i, child)| {
+                let pos = root.child_move(i).unwrap();
+                let mut pv = vec![Some(pos)];
+                pv.extend(child.pv());
+                MoveAnalysis {
+                    pos,
+                    score: 1.0 - child.winrate(),
+                    pv,
+                    visits: Some(child.n_visits as u64),
+                }
+            })
+            .collect::<Vec<_>>();
+        // stable sort keeps legal move order among equally visited moves
+        analysis.sort_by_key(|a| std::cmp::Reverse(a.visits));
+        analysis.truncate(multi_pv.unwrap_or(usize::MAX));
+        self.keep_root(root);
+        analysis
+    }
+}
+
+const DEFAULT_MARGIN_TIME: f64 = 0.002;
+const DEFAULT_CHECK_INTERVAL: usize = 100;
+/// Own move and opponent reply.
+const MAX_REUSE_PLIES: usize = 2;
+impl Search for MctsSearch {
+    fn get_move(&self, board: &mut Board) -> Option<usize> {
+        if board.is_pass() {
+            return None;
+        }
+        self.search(board, &SearchControl::default()).best_move
+    }
+
+    fn get_move_with_timeout(&self, board: &mut Board, timeout: Duration) -> Option<usize> {
+        if board.is_pass() {
+            return None;
+        }
+        let control = SearchControl {
+            timeout: Some(timeout),
+            ..Default::default()
+        };
+        self.search(board, &control).best_move
+    }
+
+    fn get_search_score(&self, board: &mut Board) -> f64 {
+        self.search_score(board, &SearchControl::default())
+    }
+}
+
+impl ControlledSearch for MctsSearch {
+    fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult {
+        MctsSearch::search(self, board, control)
+    }
+}
@@ -1,36 +1,137 @@
 use std::sync::Arc;
 
+use pyo3::exceptions::{PyTimeoutError, PyValueError};
 use pyo3::prelude::*;
 
 use crate::board::Board;
 use rust_reversi_core::board::Board as RustBoard;
-use rust_reversi_core::search::{
-    AlphaBetaSearch as RustAlphaBetaSearch, Search, ThunderSearch as RustThunderSearch,
-};
 use rust_reversi_core::search::{
     BitMatrixEvaluator as RustBitMatrixEvaluator, Evaluator as RustEvaluator,
     LegalNumEvaluator as RustLegalNumEvaluator, MatrixEvaluator as RustMatrixEvaluator,
-    MctsSearch as RustMctsSearch, PieceEvaluator as RustPieceEvaluator,
-    WinrateEvaluator as RustWinrateEvaluator,
+    PieceEvaluator as RustPieceEvaluator, WinrateEvaluator as RustWinrateEvaluator,
 };
 
+mod alpha_beta;
+mod batch;
+mod control;
+mod endgame;
+mod handle;
+mod mcts;
+mod puct;
+mod result;
+mod rollout;
+mod thunder;
+mod time_keeper;
+mod transposition;
+use alpha_beta::AlphaBetaSearch as RustAlphaBetaSearch;
+use batch::{BatchEvaluator, BatchWinrateEvaluator};
+use endgame::{EndgameResult, EndgameSolver as RustEndgameSolver, SolveMode};
+pub use handle::SearchHandle;
+use handle::{run_search, start_search, RunningSearches, SearchOptions};
+use mcts::MctsSearch as RustMctsSearch;
+use puct::{
+    PolicyValueEvaluator as RustPolicyValueEvaluator, PuctSearch as RustPuctSearch,
+    UniformPolicyValueEvaluator,
+};
+pub use result::{MoveAnalysis, SearchPolicy, SearchResult};
+use rollout::{RolloutMoves, RolloutPolicy as RustRolloutPolicy};
+use thunder::ThunderSearch as RustThunderSearch;
+
+/// Call the `evaluate` method of a Python evaluator.
+/// # Note
+/// * Searches run without the GIL, so each call takes it back.
+/// * An exception is handed to `running`, which stops the searches so that it can be
+///   re-raised. `neutral` is returned instead, also for every call after the exception.
+fn call_py_evaluator<T>(
+    py_evaluator: &Py<PyAny>,
+    running: &RunningSearches,
+    board: &RustBoard,
+    neutral: T,
+) -> T
+where
+    T: for<'py> FromPyObject<'py>,
+{
+    if running.has_failed() {
+        return neutral;
+    }
+    let result = Python::with_gil(|py| {
+        let board_wrapper = Board::from(board.clone());
+        py_evaluator
+            .call_method1(py, "evaluate", (board_wrapper,))?
+            .extract(py)
+    });
+    result.unwrap_or_else(|err| {
+        running.fail(err);
+        neutral
+    })
+}
+
+/// Call the `evaluate_batch` method of a Python evaluator with a list of boards.
+/// # Note
+/// * The method may return any sequence of numbers, e.g. a list or a 1-d ndarray,
+///   with one value per board.
+/// * Exceptions are handled as in `call_py_evaluator`.
+fn call_py_batch_evaluator(
+    py_evaluator: &Py<PyAny>,
+    running: &RunningSearches,
+    boards: &[RustBoard],
+    neutral: f64,
+) -> Vec<f64> {
+    if running.has_failed() {
+        return vec![neutral; boards.len()];
+    }
+    let result = Python::with_gil(|py| {
+        let board_wrappers = boards
+            .iter()
+            .map(|board| Board::from(board.clone()))
+            .collect::<Vec<_>>();
+        let values: Vec<f64> = py_evaluator
+            .call_method1(py, "evaluate_batch", (board_wrappers,))?
+            .extract(py)?;
+        if values.len() != boards.len() {
+            return Err(PyValueError::new_err(format!(
+                "evaluate_batch returned {} values for {} boards",
+                values.len(),
+                boards.len()
+            )));
+        }
+        Ok(values)
+    });
+    result.unwrap_or_else(|err| {
+        running.fail(err);
+        vec![neutral; boards.len()]
+    })
+}
+
+/// Whether a Python evaluator defines `evaluate_batch`.
+fn has_evaluate_batch(py_evaluator: &Py<PyAny>) -> bool {
+    Python::with_gil(|py| {
+        py_evaluator
+            .bind(py)
+            .hasattr("evaluate_batch")
+            .unwrap_or(false)
+    })
+}
+
 #[derive(Clone, Debug)]
 struct PyEvaluator {
     py_evaluator: Arc<Py<PyAny>>,
+    running: Arc<RunningSearches>,
 }
 
 impl RustEvaluator for PyEvaluator {
     fn evaluate(&self, board: &mut RustBoard) -> i32 {
-        Python::with_gil(|py| {
-            let board_wrapper = Board {
-                inner: board.clone(),
-            };
-            let result = self
-                .py_evaluator
-                .call_method1(py, "evaluate", (board_wrapper,))
-                .expect("Failed to call evaluate method");
-            result.extract(py).expect("Failed to extract result")
-        })
+        call_py_evaluator(&self.py_evaluator, &self.running, board, 0)
+    }
+}
+
+impl BatchEvaluator for PyEvaluator {
+    /// Values are rounded to the nearest integer.
+    fn evaluate_batch(&self, boards: &[RustBoard]) -> Vec<i32> {
+        call_py_batch_evaluator(&self.py_evaluator, &self.running, boards, 0.0)
+            .into_iter()
+            .map(|value| value.round() as i32)
+            .collect()
     }
 }
 
@@ -39,16 +140,34 @@ enum EvaluatorType {
     Piece(RustPieceEvaluator),
     LegalNum(RustLegalNumEvaluator),
     Matrix(Arc<RustMatrixEvaluator>),
-    Python(PyEvaluator),
+    Python(Arc<Py<PyAny>>),
 }
 
 impl EvaluatorType {
-    fn as_evaluator(&self) -> Arc<dyn RustEvaluator> {
+    /// Evaluator for the searches tracked by `running`.
+    fn as_evaluator(&self, running: &Arc<RunningSearches>) -> Arc<dyn RustEvaluator> {
         match self {
             EvaluatorType::Piece(e) => Arc::new(e.clone()),
             EvaluatorType::LegalNum(e) => Arc::new(e.clone()),
             EvaluatorType::Matrix(e) => e.clone(),
-            EvaluatorType::Python(e) => Arc::new(e.clone()),
+            EvaluatorType::Python(e) => Arc::new(PyEvaluator {
+                py_evaluator: e.clone(),
+                running: running.clone(),
+            }),
+        }
+    }
+
+    /// Batch evaluator if this is a Python evaluator defining `evaluate_batch`.
+    fn as_batch_evaluator(
+        &self,
+        running: &Arc<RunningSearches>,
+    ) -> Option<Arc<dyn BatchEvaluator>> {
+        match self {
+            EvaluatorType::Python(e) if has_evaluate_batch(e) => Some(Arc::new(PyEvaluator {
+                py_evaluator: e.clone(),
+                running: running.clone(),
+            })),
+            _ => None,
         }
     }
 }
@@ -75,13 +194,13 @@ impl Evaluator {
     }
 
     fn set_py_evaluator(&mut self, py_evaluator: Py<PyAny>) {
-        self.inner = EvaluatorType::Python(PyEvaluator {
-            py_evaluator: Arc::new(py_evaluator),
-        });
+        self.inner = EvaluatorType::Python(Arc::new(py_evaluator));
     }
 
-    fn evaluate(&self, board: &mut Board) -> i32 {
-        self.inner.as_evaluator().evaluate(&mut board.inner)
+    fn evaluate(&self, board: &mut Board) -> PyResult<i32> {
+        let running = Arc::default();
+        let score = self.inner.as_evaluator(&running).evaluate(&mut board.inner);
+        running.take_failure().map_or(Ok(score), Err)
     }
 }
 
@@ -130,50 +249,203 @@ impl MatrixEvaluator {
 
 #[pyclass]
 pub struct AlphaBetaSearch {
-    inner: RustAlphaBetaSearch,
+    inner: Arc<RustAlphaBetaSearch>,
+    running: Arc<RunningSearches>,
 }
 
 #[pymethods]
 impl AlphaBetaSearch {
     #[new]
-    fn new(evaluator: Evaluator, max_depth: usize, win_score: i32) -> Self {
+    #[pyo3(signature = (evaluator, max_depth, win_score, tt_size_mb=16, persist_tt=false, threads=1))]
+    fn new(
+        evaluator: Evaluator,
+        max_depth: usize,
+        win_score: i32,
+        tt_size_mb: usize,
+        persist_tt: bool,
+        threads: usize,
+    ) -> Self {
         let rust_evaluator = evaluator.inner;
+        let running = Arc::default();
+        let mut inner = RustAlphaBetaSearch::new(
+            max_depth,
+            rust_evaluator.as_evaluator(&running),
+            win_score,
+            tt_size_mb,
+        );
+        if let Some(batch_evaluator) = rust_evaluator.as_batch_evaluator(&running) {
+            inner.set_batch_evaluator(batch_evaluator);
+        }
+        inner.set_persist_tt(persist_tt);
+        inner.set_threads(threads);
         AlphaBetaSearch {
-            inner: RustAlphaBetaSearch::new(max_depth, rust_evaluator.as_evaluator(), win_score),
+            inner: Arc::new(inner),
+            running,
         }
     }
 
-    fn get_move(&self, board: &mut Board) -> Option<usize> {
-        self.inner.get_move(&mut board.inner)
+    #[getter]
+    fn persist_tt(&self) -> bool {
+        self.inner.persist_tt()
+    }
+
+    #[setter(persist_tt)]
+    fn set_persist_tt(&self, persist_tt: bool) {
+        self.inner.set_persist_tt(persist_tt);
+    }
+
+    fn clear_tt(&self) {
+        self.inner.clear_tt();
+    }
+
+    fn get_move(&self, py: Python<'_>, board: &mut Board) -> PyResult<Option<usize>> {
+        self.get_move_with_options(py, board, SearchOptions::default())
     }
 
-    fn get_move_with_timeout(&self, board: &mut Board, timeout_ms: u64) -> Option<usize> {
-        let timeout = std::time::Duration::from_millis(timeout_ms);
-        self.inner.get_move_with_timeout(&mut board.inner, timeout)
+    fn get_move_with_timeout(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: u64,
+    ) -> PyResult<Option<usize>> {
+        let options = SearchOptions {
+            timeout_ms: Some(timeout_ms),
+            ..Default::default()
+        };
+        self.get_move_with_options(py, board, options)
     }
 
-    fn get_search_score(&self, board: &mut Board) -> f64 {
-        self.inner.get_search_score(&mut board.inner)
+    fn get_move_with_nodes(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        max_nodes: u64,
+    ) -> PyResult<Option<usize>> {
+        let options = SearchOptions {
+            max_nodes: Some(max_nodes),
+            ..Default::default()
+        };
+        self.get_move_with_options(py, board, options)
+    }
+
+    fn get_search_score(&self, py: Python<'_>, board: &mut Board) -> PyResult<f64> {
+        let options = SearchOptions::default();
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search(b, c).score,
+        )
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, callback=None, max_nodes=None))]
+    fn search(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: Option<u64>,
+        callback: Option<Py<PyAny>>,
+        max_nodes: Option<u64>,
+    ) -> PyResult<SearchResult> {
+        let options = SearchOptions {
+            timeout_ms,
+            max_nodes,
+            callback,
+            ..Default::default()
+        };
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search(b, c),
+        )
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, callback=None, max_nodes=None))]
+    fn start_search(
+        &self,
+        board: &Board,
+        timeout_ms: Option<u64>,
+        callback: Option<Py<PyAny>>,
+        max_nodes: Option<u64>,
+    ) -> SearchHandle {
+        let options = SearchOptions {
+            timeout_ms,
+            max_nodes,
+            callback,
+            ..Default::default()
+        };
+        start_search(
+            self.inner.clone(),
+            self.running.clone(),
+            board.inner.clone(),
+            options,
+        )
+    }
+
+    fn stop(&self) {
+        self.running.stop_all();
+    }
+
+    #[pyo3(signature = (board, multi_pv=None))]
+    fn analyze(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        multi_pv: Option<usize>,
+    ) -> PyResult<Vec<MoveAnalysis>> {
+        let options = SearchOptions::default();
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.analyze(b, multi_pv, c),
+        )
+    }
+}
+
+impl AlphaBetaSearch {
+    fn get_move_with_options(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        options: SearchOptions,
+    ) -> PyResult<Option<usize>> {
+        if board.inner.is_pass() {
+            return Ok(None);
+        }
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search(b, c).best_move,
+        )
     }
 }
 
 #[derive(Clone, Debug)]
 struct PyWinrateEvaluator {
     py_evaluator: Arc<Py<PyAny>>,
+    running: Arc<RunningSearches>,
 }
 
 impl RustWinrateEvaluator for PyWinrateEvaluator {
     fn evaluate(&self, board: &mut RustBoard) -> f64 {
-        Python::with_gil(|py| {
-            let board_wrapper = Board {
-                inner: board.clone(),
-            };
-            let result = self
-                .py_evaluator
-                .call_method1(py, "evaluate", (board_wrapper,))
-                .expect("Failed to call evaluate method");
-            result.extract(py).expect("Failed to extract result")
-        })
+        call_py_evaluator(&self.py_evaluator, &self.running, board, 0.5)
+    }
+}
+
+impl BatchWinrateEvaluator for PyWinrateEvaluator {
+    fn evaluate_batch(&self, boards: &[RustBoard]) -> Vec<f64> {
+        call_py_batch_evaluator(&self.py_evaluator, &self.running, boards, 0.5)
     }
 }
 
@@ -211,14 +483,34 @@ impl RustWinrateEvaluator for BMWinEvaluator {
 #[derive(Clone)]
 enum WinrateEvaluatorType {
     BitMatrix(BMWinEvaluator),
-    Python(PyWinrateEvaluator),
+    Python(Arc<Py<PyAny>>),
 }
 
 impl WinrateEvaluatorType {
-    fn as_evaluator(&self) -> Arc<dyn RustWinrateEvaluator> {
+    /// Evaluator for the searches tracked by `running`.
+    fn as_evaluator(&self, running: &Arc<RunningSearches>) -> Arc<dyn RustWinrateEvaluator> {
         match self {
             WinrateEvaluatorType::BitMatrix(e) => Arc::new(e.clone()),
-            WinrateEvaluatorType::Python(e) => Arc::new(e.clone()),
+            WinrateEvaluatorType::Python(e) => Arc::new(PyWinrateEvaluator {
+                py_evaluator: e.clone(),
+                running: running.clone(),
+            }),
+        }
+    }
+
+    /// Batch evaluator if this is a Python evaluator defining `evaluate_batch`.
+    fn as_batch_evaluator(
+        &self,
+        running: &Arc<RunningSearches>,
+    ) -> Option<Arc<dyn BatchWinrateEvaluator>> {
+        match self {
+            WinrateEvaluatorType::Python(e) if has_evaluate_batch(e) => {
+                Some(Arc::new(PyWinrateEvaluator {
+                    py_evaluator: e.clone(),
+                    running: running.clone(),
+                }))
+            }
+            _ => None,
         }
     }
 }
@@ -245,69 +537,934 @@ impl WinrateEvaluator {
     }
 
     fn set_py_evaluator(&mut self, py_evaluator: Py<PyAny>) {
-        self.inner = WinrateEvaluatorType::Python(PyWinrateEvaluator {
-            py_evaluator: Arc::new(py_evaluator),
-        });
+        self.inner = WinrateEvaluatorType::Python(Arc::new(py_evaluator));
     }
 
-    fn evaluate(&self, board: &mut Board) -> f64 {
-        self.inner.as_evaluator().evaluate(&mut board.inner)
+    fn evaluate(&self, board: &mut Board) -> PyResult<f64> {
+        let running = Arc::default();
+        let score = self.inner.as_evaluator(&running).evaluate(&mut board.inner);
+        running.take_failure().map_or(Ok(score), Err)
     }
 }
 
 #[pyclass]
 pub struct ThunderSearch {
-    inner: RustThunderSearch,
+    inner: Arc<RustThunderSearch>,
+    running: Arc<RunningSearches>,
 }
 
 #[pymethods]
 impl ThunderSearch {
     #[new]
-    fn new(evaluator: WinrateEvaluator, n_playouts: usize, epsilon: f64) -> Self {
+    #[pyo3(signature = (evaluator, n_playouts, epsilon, seed=None, batch_size=16, threads=1))]
+    fn new(
+        evaluator: WinrateEvaluator,
+        n_playouts: usize,
+        epsilon: f64,
+        seed: Option<u64>,
+        batch_size: usize,
+        threads: usize,
+    ) -> PyResult<Self> {
+        if !(0.0..=1.0).contains(&epsilon) {
+            return Err(PyValueError::new_err("epsilon must be between 0 and 1"));
+        }
         let rust_evaluator = evaluator.inner;
-        ThunderSearch {
-            inner: RustThunderSearch::new(n_playouts, epsilon, rust_evaluator.as_evaluator()),
+        let running = Arc::default();
+        let mut inner = RustThunderSearch::new(
+            n_playouts,
+            epsilon,
+            rust_evaluator.as_evaluator(&running),
+            seed,
+        );
+        if let Some(batch_evaluator) = rust_evaluator.as_batch_evaluator(&running) {
+            inner.set_batch_evaluator(batch_evaluator, batch_size);
         }
+        inner.set_threads(threads);
+        Ok(ThunderSearch {
+            inner: Arc::new(inner),
+            running,
+        })
     }
 
-    fn get_move(&self, board: &mut Board) -> Option<usize> {
-        self.inner.get_move(&mut board.inner)
+    fn get_move(&self, py: Python<'_>, board: &mut Board) -> PyResult<Option<usize>> {
+        self.get_move_with_options(py, board, SearchOptions::default())
     }
 
-    fn get_move_with_timeout(&self, board: &mut Board, timeout_ms: u64) -> Option<usize> {
-        let timeout = std::time::Duration::from_millis(timeout_ms);
-        self.inner.get_move_with_timeout(&mut board.inner, timeout)
+    fn get_move_with_timeout(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: u64,
+    ) -> PyResult<Option<usize>> {
+        let options = SearchOptions {
+            timeout_ms: Some(timeout_ms),
+            ..Default::default()
+        };
+        self.get_move_with_options(py, board, options)
+    }
+
+    fn get_move_with_playouts(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        n_playouts: u64,
+    ) -> PyResult<Option<usize>> {
+        let options = SearchOptions {
+            max_nodes: Some(n_playouts),
+            ..Default::default()
+        };
+        self.get_move_with_options(py, board, options)
     }
 
-    fn get_search_score(&self, board: &mut Board) -> f64 {
-        self.inner.get_search_score(&mut board.inner)
+    fn get_search_score(&self, py: Python<'_>, board: &mut Board) -> PyResult<f64> {
+        let options = SearchOptions::default();
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search_score(b, c),
+        )
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, callback=None, callback_interval=1000, n_playouts=None))]
+    fn search(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: Option<u64>,
+        callback: Option<Py<PyAny>>,
+        callback_interval: usize,
+        n_playouts: Option<u64>,
+    ) -> PyResult<SearchResult> {
+        let options = SearchOptions {
+            timeout_ms,
+            max_nodes: n_playouts,
+            callback,
+            callback_interval,
+        };
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search(b, c),
+        )
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, callback=None, callback_interval=1000, n_playouts=None))]
+    fn start_search(
+        &self,
+        board: &Board,
+        timeout_ms: Option<u64>,
+        callback: Option<Py<PyAny>>,
+        callback_interval: usize,
+        n_playouts: Option<u64>,
+    ) -> SearchHandle {
+        let options = SearchOptions {
+            timeout_ms,
+            max_nodes: n_playouts,
+            callback,
+            callback_interval,
+        };
+        start_search(
+            self.inner.clone(),
+            self.running.clone(),
+            board.inner.clone(),
+            options,
+        )
+    }
+
+    fn stop(&self) {
+        self.running.stop_all();
+    }
+
+    #[pyo3(signature = (board, n_playouts=None))]
+    fn get_policy(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        n_playouts: Option<u64>,
+    ) -> PyResult<SearchPolicy> {
+        let options = SearchOptions {
+            max_nodes: n_playouts,
+            ..Default::default()
+        };
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search_policy(b, c),
+        )
+    }
+}
+
+impl ThunderSearch {
+    fn get_move_with_options(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        options: SearchOptions,
+    ) -> PyResult<Option<usize>> {
+        if board.inner.is_pass() {
+            return Ok(None);
+        }
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search(b, c).best_move,
+        )
+    }
+}
+
+#[derive(Clone)]
+enum RolloutMovesType {
+    Random,
+    EpsilonGreedy(EvaluatorType, f64),
+    CornerFirst,
+}
+
+/// Playout policy of MctsSearch.
+#[pyclass(module = "rust_reversi")]
+#[derive(Clone)]
+pub struct RolloutPolicy {
+    moves: RolloutMovesType,
+    cutoff: Option<(usize, WinrateEvaluatorType)>,
+}
+
+impl RolloutPolicy {
+    fn new(moves: RolloutMovesType) -> Self {
+        RolloutPolicy {
+            moves,
+            cutoff: None,
+        }
+    }
+
+    /// Policy for the searches tracked by `running`.
+    fn as_rollout_policy(&self, running: &Arc<RunningSearches>) -> RustRolloutPolicy {
+        let moves = match &self.moves {
+            RolloutMovesType::Random => RolloutMoves::Random,
+            RolloutMovesType::EpsilonGreedy(evaluator, epsilon) => RolloutMoves::EpsilonGreedy {
+                evaluator: evaluator.as_evaluator(running),
+                epsilon: *epsilon,
+            },
+            RolloutMovesType::CornerFirst => RolloutMoves::CornerFirst,
+        };
+        let cutoff = self
+            .cutoff
+            .as_ref()
+            .map(|(plies, evaluator)| (*plies, evaluator.as_evaluator(running)));
+        RustRolloutPolicy { moves, cutoff }
+    }
+}
+
+#[pymethods]
+impl RolloutPolicy {
+    #[staticmethod]
+    fn random() -> Self {
+        RolloutPolicy::new(RolloutMovesType::Random)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (evaluator, epsilon=0.1))]
+    fn epsilon_greedy(evaluator: Evaluator, epsilon: f64) -> PyResult<Self> {
+        if !(0.0..=1.0).contains(&epsilon) {
+            return Err(PyValueError::new_err("epsilon must be between 0 and 1"));
+        }
+        Ok(RolloutPolicy::new(RolloutMovesType::EpsilonGreedy(
+            evaluator.inner,
+            epsilon,
+        )))
+    }
+
+    #[staticmethod]
+    fn corner_first() -> Self {
+        RolloutPolicy::new(RolloutMovesType::CornerFirst)
+    }
+
+    fn with_cutoff(&self, plies: usize, evaluator: WinrateEvaluator) -> Self {
+        RolloutPolicy {
+            cutoff: Some((plies, evaluator.inner)),
+            ..self.clone()
+        }
     }
 }
 
 #[pyclass]
 pub struct MctsSearch {
-    inner: RustMctsSearch,
+    inner: Arc<RustMctsSearch>,
+    running: Arc<RunningSearches>,
 }
 
 #[pymethods]
 impl MctsSearch {
     #[new]
-    fn new(n_playouts: usize, c: f64, expand_threshold: usize) -> Self {
+    #[pyo3(signature = (
+        n_playouts,
+        c,
+        expand_threshold,
+        seed=None,
+        reuse_tree=false,
+        threads=1,
+        rollout_policy=None,
+    ))]
+    fn new(
+        n_playouts: usize,
+        c: f64,
+        expand_threshold: usize,
+        seed: Option<u64>,
+        reuse_tree: bool,
+        threads: usize,
+        rollout_policy: Option<RolloutPolicy>,
+    ) -> Self {
+        let running = Arc::default();
+        let mut inner = RustMctsSearch::new(n_playouts, c, expand_threshold, seed);
+        inner.set_reuse_tree(reuse_tree);
+        inner.set_threads(threads);
+        if let Some(rollout_policy) = rollout_policy {
+            inner.set_rollout_policy(rollout_policy.as_rollout_policy(&running));
+        }
         MctsSearch {
-            inner: RustMctsSearch::new(n_playouts, c, expand_threshold),
+            inner: Arc::new(inner),
+            running,
         }
     }
 
-    fn get_move(&self, board: &mut Board) -> Option<usize> {
-        self.inner.get_move(&mut board.inner)
+    #[getter]
+    fn reuse_tree(&self) -> bool {
+        self.inner.reuse_tree()
+    }
+
+    #[setter(reuse_tree)]
+    fn set_reuse_tree(&self, reuse_tree: bool) {
+        self.inner.set_reuse_tree(reuse_tree);
+    }
+
+    fn clear_tree(&self) {
+        self.inner.clear_tree();
+    }
+
+    fn get_move(&self, py: Python<'_>, board: &mut Board) -> PyResult<Option<usize>> {
+        self.get_move_with_options(py, board, SearchOptions::default())
+    }
+
+    fn get_move_with_timeout(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: u64,
+    ) -> PyResult<Option<usize>> {
+        let options = SearchOptions {
+            timeout_ms: Some(timeout_ms),
+            ..Default::default()
+        };
+        self.get_move_with_options(py, board, options)
+    }
+
+    fn get_move_with_playouts(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        n_playouts: u64,
+    ) -> PyResult<Option<usize>> {
+        let options = SearchOptions {
+            max_nodes: Some(n_playouts),
+            ..Default::default()
+        };
+        self.get_move_with_options(py, board, options)
+    }
+
+    fn get_search_score(&self, py: Python<'_>, board: &mut Board) -> PyResult<f64> {
+        let options = SearchOptions::default();
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search_score(b, c),
+        )
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, callback=None, callback_interval=1000, n_playouts=None))]
+    fn search(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: Option<u64>,
+        callback: Option<Py<PyAny>>,
+        callback_interval: usize,
+        n_playouts: Option<u64>,
+    ) -> PyResult<SearchResult> {
+        let options = SearchOptions {
+            timeout_ms,
+            max_nodes: n_playouts,
+            callback,
+            callback_interval,
+        };
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search(b, c),
+        )
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, callback=None, callback_interval=1000, n_playouts=None))]
+    fn start_search(
+        &self,
+        board: &Board,
+        timeout_ms: Option<u64>,
+        callback: Option<Py<PyAny>>,
+        callback_interval: usize,
+        n_playouts: Option<u64>,
+    ) -> SearchHandle {
+        let options = SearchOptions {
+            timeout_ms,
+            max_nodes: n_playouts,
+            callback,
+            callback_interval,
+        };
+        start_search(
+            self.inner.clone(),
+            self.running.clone(),
+            board.inner.clone(),
+            options,
+        )
+    }
+
+    fn stop(&self) {
+        self.running.stop_all();
     }
 
-    fn get_move_with_timeout(&self, board: &mut Board, timeout_ms: u64) -> Option<usize> {
-        let timeout = std::time::Duration::from_millis(timeout_ms);
-        self.inner.get_move_with_timeout(&mut board.inner, timeout)
+    #[pyo3(signature = (board, multi_pv=None))]
+    fn analyze(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        multi_pv: Option<usize>,
+    ) -> PyResult<Vec<MoveAnalysis>> {
+        let options = SearchOptions::default();
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.analyze(b, multi_pv, c),
+        )
     }
 
-    fn get_search_score(&self, board: &mut Board) -> f64 {
-        self.inner.get_search_score(&mut board.inner)
+    #[pyo3(signature = (board, n_playouts=None))]
+    fn get_policy(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        n_playouts: Option<u64>,
+    ) -> PyResult<SearchPolicy> {
+        let options = SearchOptions {
+            max_nodes: n_playouts,
+            ..Default::default()
+        };
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search_policy(b, c),
+        )
+    }
+}
+
+impl MctsSearch {
+    fn get_move_with_options(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        options: SearchOptions,
+    ) -> PyResult<Option<usize>> {
+        if board.inner.is_pass() {
+            return Ok(None);
+        }
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search(b, c).best_move,
+        )
+    }
+}
+
+/// Check that a policy from a Python evaluator covers every square.
+fn check_policy(policy: &[f64]) -> PyResult<()> {
+    if !(SearchPolicy::PASS..=SearchPolicy::SIZE).contains(&policy.len()) {
+        return Err(PyValueError::new_err(format!(
+            "policy must have 64 or 65 values, got {}",
+            policy.len()
+        )));
+    }
+    Ok(())
+}
+
+fn check_temperature(temperature: f64) -> PyResult<()> {
+    if !(0.0..).contains(&temperature) {
+        return Err(PyValueError::new_err("temperature must be non-negative"));
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+struct PyPolicyValueEvaluator {
+    py_evaluator: Arc<Py<PyAny>>,
+    running: Arc<RunningSearches>,
+    batched: bool,
+}
+
+impl RustPolicyValueEvaluator for PyPolicyValueEvaluator {
+    /// Calls `evaluate`, handling exceptions as in `call_py_evaluator`.
+    fn evaluate(&self, board: &RustBoard) -> (Vec<f64>, f64) {
+        if self.running.has_failed() {
+            return (Vec::new(), 0.5);
+        }
+        let result = Python::with_gil(|py| {
+            let board_wrapper = Board::from(board.clone());
+            let (policy, value): (Vec<f64>, f64) = self
+                .py_evaluator
+                .call_method1(py, "evaluate", (board_wrapper,))?
+                .extract(py)?;
+            check_policy(&policy)?;
+            Ok((policy, value))
+        });
+        result.unwrap_or_else(|err| {
+            self.running.fail(err);
+            (Vec::new(), 0.5)
+        })
+    }
+
+    /// Calls `evaluate_batch` if the evaluator defines it.
+    fn evaluate_batch(&self, boards: &[RustBoard]) -> Vec<(Vec<f64>, f64)> {
+        if !self.batched {
+            return boards.iter().map(|board| self.evaluate(board)).collect();
+        }
+        let neutral = vec![(Vec::new(), 0.5); boards.len()];
+        if self.running.has_failed() {
+            return neutral;
+        }
+        let result = Python::with_gil(|py| {
+            let board_wrappers = boards
+                .iter()
+                .map(|board| Board::from(board.clone()))
+                .collect::<Vec<_>>();
+            let (policies, values): (Vec<Vec<f64>>, Vec<f64>) = self
+                .py_evaluator
+                .call_method1(py, "evaluate_batch", (board_wrappers,))?
+                .extract(py)?;
+            if policies.len() != boards.len() || values.len() != boards.len() {
+                return Err(PyValueError::new_err(format!(
+                    "evaluate_batch returned {} policies and {} values for {} boards",
+                    policies.len(),
+                    values.len(),
+                    boards.len()
+                )));
+            }
+            for policy in &policies {
+                check_policy(policy)?;
+            }
+            Ok(policies.into_iter().zip(values).collect())
+        });
+        result.unwrap_or_else(|err| {
+            self.running.fail(err);
+            neutral
+        })
+    }
+}
+
+#[derive(Clone)]
+enum PolicyValueEvaluatorType {
+    Uniform(UniformPolicyValueEvaluator),
+    Python(Arc<Py<PyAny>>),
+}
+
+impl PolicyValueEvaluatorType {
+    /// Evaluator for the searches tracked by `running`.
+    fn as_evaluator(&self, running: &Arc<RunningSearches>) -> Arc<dyn RustPolicyValueEvaluator> {
+        match self {
+            PolicyValueEvaluatorType::Uniform(e) => Arc::new(e.clone()),
+            PolicyValueEvaluatorType::Python(e) => Arc::new(PyPolicyValueEvaluator {
+                py_evaluator: e.clone(),
+                running: running.clone(),
+                batched: has_evaluate_batch(e),
+            }),
+        }
+    }
+
+    fn is_batched(&self) -> bool {
+        match self {
+            PolicyValueEvaluatorType::Python(e) => has_evaluate_batch(e),
+            _ => false,
+        }
+    }
+}
+
+#[pyclass(subclass)]
+#[derive(Clone)]
+pub struct PolicyValueEvaluator {
+    inner: PolicyValueEvaluatorType,
+}
+
+impl Default for PolicyValueEvaluator {
+    fn default() -> Self {
+        let value = WinrateEvaluator::default()
+            .inner
+            .as_evaluator(&Arc::default());
+        PolicyValueEvaluator {
+            inner: PolicyValueEvaluatorType::Uniform(UniformPolicyValueEvaluator::new(value)),
+        }
+    }
+}
+
+#[pymethods]
+impl PolicyValueEvaluator {
+    #[new]
+    fn new() -> Self {
+        PolicyValueEvaluator::default()
+    }
+
+    fn set_py_evaluator(&mut self, py_evaluator: Py<PyAny>) {
+        self.inner = PolicyValueEvaluatorType::Python(Arc::new(py_evaluator));
+    }
+
+    fn evaluate(&self, board: &Board) -> PyResult<(Vec<f64>, f64)> {
+        let running = Arc::default();
+        let result = self.inner.as_evaluator(&running).evaluate(&board.inner);
+        running.take_failure().map_or(Ok(result), Err)
+    }
+}
+
+#[pyclass]
+pub struct PuctSearch {
+    inner: Arc<RustPuctSearch>,
+    running: Arc<RunningSearches>,
+}
+
+#[pymethods]
+impl PuctSearch {
+    #[new]
+    #[pyo3(signature = (
+        evaluator,
+        n_playouts,
+        c_puct=1.5,
+        dirichlet_alpha=None,
+        dirichlet_epsilon=0.25,
+        temperature=0.0,
+        seed=None,
+        batch_size=16,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        evaluator: PolicyValueEvaluator,
+        n_playouts: usize,
+        c_puct: f64,
+        dirichlet_alpha: Option<f64>,
+        dirichlet_epsilon: f64,
+        temperature: f64,
+        seed: Option<u64>,
+        batch_size: usize,
+    ) -> PyResult<Self> {
+        if !(0.0..).contains(&c_puct) {
+            return Err(PyValueError::new_err("c_puct must be non-negative"));
+        }
+        if dirichlet_alpha.is_some_and(|alpha| alpha.is_nan() || alpha <= 0.0) {
+            return Err(PyValueError::new_err("dirichlet_alpha must be positive"));
+        }
+        if !(0.0..=1.0).contains(&dirichlet_epsilon) {
+            return Err(PyValueError::new_err(
+                "dirichlet_epsilon must be between 0 and 1",
+            ));
+        }
+        check_temperature(temperature)?;
+        let rust_evaluator = evaluator.inner;
+        let running = Arc::default();
+        let mut inner = RustPuctSearch::new(
+            n_playouts,
+            c_puct,
+            rust_evaluator.as_evaluator(&running),
+            seed,
+        );
+        if let Some(alpha) = dirichlet_alpha {
+            inner.set_dirichlet_noise(alpha, dirichlet_epsilon);
+        }
+        inner.set_temperature(temperature);
+        if rust_evaluator.is_batched() {
+            inner.set_batch_size(batch_size);
+        }
+        Ok(PuctSearch {
+            inner: Arc::new(inner),
+            running,
+        })
+    }
+
+    fn get_move(&self, py: Python<'_>, board: &mut Board) -> PyResult<Option<usize>> {
+        self.get_move_with_options(py, board, SearchOptions::default())
+    }
+
+    fn get_move_with_timeout(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: u64,
+    ) -> PyResult<Option<usize>> {
+        let options = SearchOptions {
+            timeout_ms: Some(timeout_ms),
+            ..Default::default()
+        };
+        self.get_move_with_options(py, board, options)
+    }
+
+    fn get_move_with_playouts(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        n_playouts: u64,
+    ) -> PyResult<Option<usize>> {
+        let options = SearchOptions {
+            max_nodes: Some(n_playouts),
+            ..Default::default()
+        };
+        self.get_move_with_options(py, board, options)
+    }
+
+    #[pyo3(signature = (board, temperature=None, n_playouts=None))]
+    fn get_move_and_policy(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        temperature: Option<f64>,
+        n_playouts: Option<u64>,
+    ) -> PyResult<(Option<usize>, Vec<f64>)> {
+        if let Some(temperature) = temperature {
+            check_temperature(temperature)?;
+        }
+        let options = SearchOptions {
+            max_nodes: n_playouts,
+            ..Default::default()
+        };
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| {
+                let (best_move, policy) = s.search_policy(b, temperature, c);
+                (best_move, policy.policy)
+            },
+        )
+    }
+
+    #[pyo3(signature = (board, n_playouts=None))]
+    fn get_policy(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        n_playouts: Option<u64>,
+    ) -> PyResult<SearchPolicy> {
+        let options = SearchOptions {
+            max_nodes: n_playouts,
+            ..Default::default()
+        };
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search_policy(b, None, c).1,
+        )
+    }
+
+    fn get_search_score(&self, py: Python<'_>, board: &mut Board) -> PyResult<f64> {
+        let options = SearchOptions::default();
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search_score(b, c),
+        )
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, callback=None, callback_interval=1000, n_playouts=None))]
+    fn search(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: Option<u64>,
+        callback: Option<Py<PyAny>>,
+        callback_interval: usize,
+        n_playouts: Option<u64>,
+    ) -> PyResult<SearchResult> {
+        let options = SearchOptions {
+            timeout_ms,
+            max_nodes: n_playouts,
+            callback,
+            callback_interval,
+        };
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search(b, c),
+        )
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, callback=None, callback_interval=1000, n_playouts=None))]
+    fn start_search(
+        &self,
+        board: &Board,
+        timeout_ms: Option<u64>,
+        callback: Option<Py<PyAny>>,
+        callback_interval: usize,
+        n_playouts: Option<u64>,
+    ) -> SearchHandle {
+        let options = SearchOptions {
+            timeout_ms,
+            max_nodes: n_playouts,
+            callback,
+            callback_interval,
+        };
+        start_search(
+            self.inner.clone(),
+            self.running.clone(),
+            board.inner.clone(),
+            options,
+        )
+    }
+
+    fn stop(&self) {
+        self.running.stop_all();
+    }
+}
+
+impl PuctSearch {
+    /// Move sampled at the temperature set at construction.
+    fn get_move_with_options(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        options: SearchOptions,
+    ) -> PyResult<Option<usize>> {
+        if board.inner.is_pass() {
+            return Ok(None);
+        }
+        run_search(
+            py,
+            &*self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.search_policy(b, None, c).0,
+        )
+    }
+}
+
+#[pyclass]
+pub struct EndgameSolver {
+    inner: RustEndgameSolver,
+    running: Arc<RunningSearches>,
+}
+
+#[pymethods]
+impl EndgameSolver {
+    #[new]
+    fn new() -> Self {
+        EndgameSolver {
+            inner: RustEndgameSolver::new(),
+            running: Arc::new(RunningSearches::default()),
+        }
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, max_nodes=None))]
+    fn solve(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: Option<u64>,
+        max_nodes: Option<u64>,
+    ) -> PyResult<(i32, Vec<usize>)> {
+        let result = self.solve_with_limits(py, board, SolveMode::Exact, timeout_ms, max_nodes)?;
+        Ok((result.score, result.best_moves))
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, max_nodes=None))]
+    fn solve_win_loss(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: Option<u64>,
+        max_nodes: Option<u64>,
+    ) -> PyResult<(i32, Vec<usize>)> {
+        let result =
+            self.solve_with_limits(py, board, SolveMode::WinLossDraw, timeout_ms, max_nodes)?;
+        Ok((result.score, result.best_moves))
+    }
+
+    #[pyo3(signature = (board, timeout_ms=None, max_nodes=None))]
+    fn get_move(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        timeout_ms: Option<u64>,
+        max_nodes: Option<u64>,
+    ) -> PyResult<Option<usize>> {
+        let result = self.solve_with_limits(py, board, SolveMode::Exact, timeout_ms, max_nodes)?;
+        Ok(result.best_moves.first().copied())
+    }
+
+    fn stop(&self) {
+        self.running.stop_all();
+    }
+}
+
+impl EndgameSolver {
+    /// Solve under the given limits. A solve stopped before finishing raises TimeoutError,
+    /// as a partial solve proves nothing.
+    fn solve_with_limits(
+        &self,
+        py: Python<'_>,
+        board: &mut Board,
+        mode: SolveMode,
+        timeout_ms: Option<u64>,
+        max_nodes: Option<u64>,
+    ) -> PyResult<EndgameResult> {
+        let options = SearchOptions {
+            timeout_ms,
+            max_nodes,
+            ..Default::default()
+        };
+        run_search(
+            py,
+            &self.inner,
+            &self.running,
+            &mut board.inner,
+            options,
+            |s, b, c| s.solve(b, mode, c),
+        )?
+        .ok_or_else(|| PyTimeoutError::new_err("Solve stopped before finishing"))
     }
 }
@@ -0,0 +1,560 @@
+use std::f64::consts::PI;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_reversi_core::board::Board;
+use rust_reversi_core::search::{Search, WinrateEvaluator};
+
+use super::control::{ControlledSearch, SearchControl};
+use super::result::{SearchPolicy, SearchResult};
+
+/// Evaluator giving move priors and the value of a position, e.g. a policy/value network.
+pub trait PolicyValueEvaluator: Send + Sync + Debug {
+    /// Priors indexed by square (at least 64 values, the pass entry is ignored)
+    /// and winrate for the side to move.
+    fn evaluate(&self, board: &Board) -> (Vec<f64>, f64);
+
+    /// Same as `evaluate` for many boards, in the same order.
+    fn evaluate_batch(&self, boards: &[Board]) -> Vec<(Vec<f64>, f64)> {
+        boards.iter().map(|board| self.evaluate(board)).collect()
+    }
+}
+
+/// Same prior for every legal move and the value of a winrate evaluator.
+#[derive(Debug, Clone)]
+pub struct UniformPolicyValueEvaluator {
+    evaluator: Arc<dyn WinrateEvaluator>,
+}
+
+impl UniformPolicyValueEvaluator {
+    pub fn new(evaluator: Arc<dyn WinrateEvaluator>) -> Self {
+        Self { evaluator }
+    }
+}
+
+impl PolicyValueEvaluator for UniformPolicyValueEvaluator {
+    fn evaluate(&self, board: &Board) -> (Vec<f64>, f64) {
+        let mut board = board.clone();
+        let legal_moves = board.get_legal_moves_vec().to_vec();
+        let mut policy = vec![0.0; SearchPolicy::SIZE];
+        for &m in &legal_moves {
+            policy[m] = 1.0 / legal_moves.len() as f64;
+        }
+        if legal_moves.is_empty() {
+            policy[SearchPolicy::PASS] = 1.0;
+        }
+        (policy, self.evaluator.evaluate(&mut board))
+    }
+}
+
+/// Value of a finished game for the side to move.
+fn terminal_score(board: &Board) -> f64 {
+    match (board.is_win(), board.is_lose()) {
+        (Ok(true), _) => 1.0,
+        (_, Ok(true)) => 0.0,
+        _ => 0.5,
+    }
+}
+
+/// Standard normal sample (Box-Muller).
+fn sample_normal(rng: &mut StdRng) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+/// Gamma(alpha, 1) sample (Marsaglia-Tsang).
+fn sample_gamma(rng: &mut StdRng, alpha: f64) -> f64 {
+    if alpha < 1.0 {
+        let u = rng.gen::<f64>();
+        return sample_gamma(rng, alpha + 1.0) * u.powf(1.0 / alpha);
+    }
+    let d = alpha - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = sample_normal(rng);
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u = rng.gen::<f64>();
+        if u < 1.0 - 0.0331 * x.powi(4) || u.ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+            return d * v;
+        }
+    }
+}
+
+/// Value added to a node per visit while its leaf waits for a batch evaluation.
+const VIRTUAL_LOSS: f64 = 1.0;
+
+struct PuctNode {
+    board: Board,
+    prior: f64,
+    w: f64,
+    n_visits: usize,
+    children: Option<Vec<PuctNode>>,
+}
+
+impl PuctNode {
+    fn new(board: Board, prior: f64) -> Self {
+        Self {
+            board,
+            prior,
+            w: 0.0,
+            n_visits: 0,
+            children: None,
+        }
+    }
+
+    /// Mean value for the side to move, 0.5 before the first visit.
+    fn winrate(&self) -> f64 {
+        if self.n_visits > 0 {
+            self.w / self.n_visits as f64
+        } else {
+            0.5
+        }
+    }
+
+    /// Create the children with priors taken from `policy`, normalized over the legal moves.
+    /// # Note
+    /// * Negative or non-finite priors count as 0. If no legal move has a positive prior,
+    ///   every legal move gets the same prior.
+    fn expand(&mut self, policy: &[f64]) {
+        let mut board = self.board.clone();
+        if board.is_pass() {
+            board.do_pass().unwrap();
+            self.children = Some(vec![PuctNode::new(board, 1.0)]);
+            return;
+        }
+        let legal_moves = board.get_legal_moves_vec().to_vec();
+        let priors = legal_moves
+            .iter()
+            .map(|&m| policy.get(m).copied().filter(|p| p.is_finite() && *p > 0.0))
+            .map(|p| p.unwrap_or(0.0))
+            .collect::<Vec<_>>();
+        let sum = priors.iter().sum::<f64>();
+        let children = legal_moves
+            .iter()
+            .zip(priors)
+            .map(|(&m, prior)| {
+                let mut child = board.clone();
+                child.do_move(m).unwrap();
+                let prior = if sum > 0.0 {
+                    prior / sum
+                } else {
+                    1.0 / legal_moves.len() as f64
+                };
+                PuctNode::new(child, prior)
+            })
+            .collect();
+        self.children = Some(children);
+    }
+
+    /// Mix Dirichlet noise into the priors of the children.
+    fn add_noise(&mut self, alpha: f64, epsilon: f64, rng: &mut StdRng) {
+        let children = self.children.as_mut().unwrap();
+        if children.len() < 2 {
+            return;
+        }
+        let noise = children
+            .iter()
+            .map(|_| sample_gamma(rng, alpha))
+            .collect::<Vec<_>>();
+        let sum = noise.iter().sum::<f64>();
+        if sum <= 0.0 {
+            return;
+        }
+        for (child, eta) in children.iter_mut().zip(noise) {
+            child.prior = (1.0 - epsilon) * child.prior + epsilon * eta / sum;
+        }
+    }
+
+    /// Child maximizing Q + U. Unvisited children take the value of this node as Q.
+    fn select_child_index(&self, c_puct: f64) -> usize {
+        let children = self.children.as_ref().unwrap();
+        let fpu = self.winrate();
+        let sqrt_n = (self.n_visits as f64).sqrt();
+        let mut best_child_index = 0;
+        let mut best_score = f64::NEG_INFINITY;
+        for (i, child) in children.iter().enumerate() {
+            let q = if child.n_visits > 0 {
+                1.0 - child.w / child.n_visits as f64
+            } else {
+                fpu
+            };
+            let score = q + c_puct * child.prior * sqrt_n / (1 + child.n_visits) as f64;
+            if score > best_score {
+                best_score = score;
+                best_child_index = i;
+            }
+        }
+        best_child_index
+    }
+
+    /// Walk down to a leaf, adding a virtual loss to every node on the way
+    /// so that the next walks of the same batch spread over other leaves.
+    /// Returns the child indices leading to the leaf in `path`.
+    fn select_leaf(&mut self, path: &mut Vec<usize>, c_puct: f64) {
+        self.n_visits += 1;
+        self.w += VIRTUAL_LOSS;
+        if self.board.is_game_over() || self.children.is_none() {
+            return;
+        }
+        let child_index = self.select_child_index(c_puct);
+        path.push(child_index);
+        self.children.as_mut().unwrap()[child_index].select_leaf(path, c_puct);
+    }
+
+    /// Replace the virtual losses of `select_leaf` along `path` with the value of the leaf,
+    /// expanding it with `policy` if needed.
+    fn backup(&mut self, path: &[usize], leaf_value: f64, policy: &[f64]) -> f64 {
+        let value = match path.split_first() {
+            Some((&child_index, rest)) => {
+                let child = &mut self.children.as_mut().unwrap()[child_index];
+                1.0 - child.backup(rest, leaf_value, policy)
+            }
+            None => {
+                if self.children.is_none() && !self.board.is_game_over() {
+                    self.expand(policy);
+                }
+                leaf_value
+            }
+        };
+        self.w += value - VIRTUAL_LOSS;
+        value
+    }
+
+    fn node(&self, path: &[usize]) -> &PuctNode {
+        path.iter()
+            .fold(self, |node, &i| &node.children.as_ref().unwrap()[i])
+    }
+
+    /// Run `n` playouts whose leaves are evaluated in one batch.
+    fn evaluate_batch(&mut self, n: usize, evaluator: &dyn PolicyValueEvaluator, c_puct: f64) {
+        let paths = (0..n)
+            .map(|_| {
+                let mut path = Vec::new();
+                self.select_leaf(&mut path, c_puct);
+                path
+            })
+            .collect::<Vec<_>>();
+        let leaves = paths
+            .iter()
+            .map(|path| &self.node(path).board)
+            .filter(|board| !board.is_game_over())
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut evaluations = evaluator.evaluate_batch(&leaves).into_iter();
+        for path in &paths {
+            let board = &self.node(path).board;
+            let (policy, value) = if board.is_game_over() {
+                (Vec::new(), terminal_score(board))
+            } else {
+                evaluations.next().unwrap_or((Vec::new(), 0.5))
+            };
+            self.backup(path, value, &policy);
+        }
+    }
+
+    fn best_child_index(&self) -> usize {
+        let mut best_child_index = 0;
+        let mut best_n_visits = 0;
+        for (i, child) in self.children.as_ref().unwrap().iter().enumerate() {
+            if child.n_visits > best_n_visits {
+                best_n_visits = child.n_visits;
+                best_child_index = i;
+            }
+        }
+        best_child_index
+    }
+
+    /// Child sampled in proportion to visits^(1/temperature).
+    /// A temperature of 0 picks the most visited child.
+    fn sample_child_index(&self, temperature: f64, rng: &mut StdRng) -> usize {
+        let best_child_index = self.best_child_index();
+        let children = self.children.as_ref().unwrap();
+        let max_visits = children[best_child_index].n_visits as f64;
+        if temperature <= 0.0 || max_visits == 0.0 {
+            return best_child_index;
+        }
+        // scaled by the most visited child so that low temperatures do not overflow
+        let weights = children
+            .iter()
+            .map(|child| (child.n_visits as f64 / max_visits).powf(1.0 / temperature))
+            .collect::<Vec<_>>();
+        let mut x = rng.gen::<f64>() * weights.iter().sum::<f64>();
+        for (i, weight) in weights.iter().enumerate() {
+            if x < *weight {
+                return i;
+            }
+            x -= weight;
+        }
+        best_child_index
+    }
+
+    /// Move leading to the child at `index`. None for the pass child.
+    fn child_move(&self, index: usize) -> Option<usize> {
+        let mut board = self.board.clone();
+        if board.is_pass() {
+            None
+        } else {
+            Some(board.get_legal_moves_vec()[index])
+        }
+    }
+
+    /// Visits and winrates of the children.
+    fn policy(&self, nodes: u64) -> SearchPolicy {
+        let children = self.children.as_ref().unwrap().iter().enumerate();
+        SearchPolicy::from_children(
+            children.map(|(i, child)| {
+                let n_visits = child.n_visits as f64;
+                (
+                    self.child_move(i),
+                    child.n_visits as u64,
+                    n_visits - child.w,
+                )
+            }),
+            nodes,
+        )
+    }
+
+    /// Most visited line from this node.
+    fn pv(&self) -> Vec<Option<usize>> {
+        let mut pv = Vec::new();
+        let mut node = self;
+        while let Some(children) = &node.children {
+            let index = node.best_child_index();
+            if children[index].n_visits == 0 {
+                break;
+            }
+            pv.push(node.child_move(index));
+            node = &children[index];
+        }
+        pv
+    }
+
+    /// Deepest visited ply below this node.
+    fn depth(&self) -> usize {
+        match &self.children {
+            Some(children) => children
+                .iter()
+                .filter(|child| child.n_visits > 0)
+                .map(|child| child.depth() + 1)
+                .max()
+                .unwrap_or(0),
+            None => 0,
+        }
+    }
+}
+
+/// PUCT search as in AlphaZero: MCTS guided by the priors and values of a policy/value evaluator.
+/// # Note
+/// * Optional Dirichlet noise is mixed into the root priors for exploration in self-play.
+/// * `get_move` samples the move in proportion to visits^(1/temperature);
+///   `search` always reports the most visited move.
+/// * With `batch_size` > 1, leaves are selected with virtual losses and evaluated together.
+#[derive(Debug)]
+pub struct PuctSearch {
+    n_playouts: usize,
+    c_puct: f64,
+    evaluator: Arc<dyn PolicyValueEvaluator>,
+    dirichlet_alpha: Option<f64>,
+    dirichlet_epsilon: f64,
+    temperature: f64,
+    batch_size: usize,
+    margin_time: f64,
+    check_interval: usize,
+    rng: Mutex<StdRng>,
+}
+
+impl PuctSearch {
+    /// Create a new PuctSearch instance.
+    /// # Arguments
+    /// * `n_playouts` - The number of playouts to run.
+    /// * `c_puct` - The exploration parameter.
+    /// * `evaluator` - The evaluator giving priors and values.
+    /// * `seed` - Seed of the RNG. None seeds from the OS.
+    pub fn new(
+        n_playouts: usize,
+        c_puct: f64,
+        evaluator: Arc<dyn PolicyValueEvaluator>,
+        seed: Option<u64>,
+    ) -> Self {
+        Self {
+            n_playouts,
+            c_puct,
+            evaluator,
+            dirichlet_alpha: None,
+            dirichlet_epsilon: 0.25,
+            temperature: 0.0,
+            batch_size: 1,
+            margin_time: DEFAULT_MARGIN_TIME,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            rng: Mutex::new(match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            }),
+        }
+    }
+
+    /// Mix Dirichlet(alpha) noise into the root priors with weight `epsilon`.
+    pub fn set_dirichlet_noise(&mut self, alpha: f64, epsilon: f64) {
+        self.dirichlet_alpha = Some(alpha);
+        self.dirichlet_epsilon = epsilon;
+    }
+
+    /// Temperature used by `get_move`.
+    pub fn set_temperature(&mut self, temperature: f64) {
+        self.temperature = temperature;
+    }
+
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size.max(1);
+    }
+
+    fn new_rng(&self) -> StdRng {
+        // each search draws its own seed, so a seeded instance gives the same sequence of moves
+        StdRng::seed_from_u64(self.rng.lock().unwrap().gen())
+    }
+
+    /// Expand the root and run the playouts, stopping early on timeout or stop.
+    /// Returns the root, the number of playouts run and the number of playouts planned.
+    /// # Note
+    /// * `control.max_nodes` overrides the number of playouts set at construction.
+    /// * The evaluation of the root is not counted as a playout.
+    fn run(
+        &self,
+        board: &Board,
+        control: &SearchControl,
+        start: Instant,
+        rng: &mut StdRng,
+    ) -> (PuctNode, usize, usize) {
+        let time_keeper = control.time_keeper(self.margin_time);
+        let mut root = PuctNode::new(board.clone(), 1.0);
+        let (policy, value) = self.evaluator.evaluate(board);
+        root.expand(&policy);
+        root.w = value;
+        root.n_visits = 1;
+        if let Some(alpha) = self.dirichlet_alpha {
+            root.add_noise(alpha, self.dirichlet_epsilon, rng);
+        }
+        let n_playouts = control.max_nodes.map_or(self.n_playouts, |n| n as usize);
+        let mut playouts = 0;
+        while playouts < n_playouts {
+            let before = playouts;
+            let n = self.batch_size.min(n_playouts - playouts);
+            root.evaluate_batch(n, self.evaluator.as_ref(), self.c_puct);
+            playouts += n;
+            let interval = control.progress_interval;
+            if interval > 0 && playouts / interval > before / interval {
+                control.report(&self.make_result(&root, playouts, n_playouts, start));
+            }
+            if self.batch_size > 1 || before % self.check_interval == 0 {
+                let timed_out = time_keeper.as_ref().is_some_and(|t| t.is_timeout());
+                if timed_out || control.is_stopped() {
+                    break;
+                }
+            }
+        }
+        (root, playouts, n_playouts)
+    }
+
+    fn make_result(
+        &self,
+        root: &PuctNode,
+        playouts: usize,
+        n_playouts: usize,
+        start: Instant,
+    ) -> SearchResult {
+        let index = root.best_child_index();
+        let best_child = &root.children.as_ref().unwrap()[index];
+        SearchResult {
+            best_move: root.child_move(index),
+            score: 1.0 - best_child.winrate(),
+            pv: root.pv(),
+            depth: root.depth(),
+            nodes: playouts as u64,
+            elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+            timed_out: playouts < n_playouts,
+        }
+    }
+
+    /// Search the board and report the principal variation and statistics.
+    pub fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult {
+        let start = Instant::now();
+        if board.is_game_over() {
+            return SearchResult {
+                score: terminal_score(board),
+                elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+                ..Default::default()
+            };
+        }
+        let mut rng = self.new_rng();
+        let (root, playouts, n_playouts) = self.run(board, control, start, &mut rng);
+        self.make_result(&root, playouts, n_playouts, start)
+    }
+
+    /// Winrate of the root for the side to move.
+    pub fn search_score(&self, board: &mut Board, control: &SearchControl) -> f64 {
+        if board.is_game_over() {
+            return terminal_score(board);
+        }
+        let mut rng = self.new_rng();
+        let (root, ..) = self.run(board, control, Instant::now(), &mut rng);
+        root.winrate()
+    }
+
+    /// Move sampled at `temperature` and the visits and winrates of the root moves.
+    /// # Note
+    /// * None uses the temperature set at construction.
+    /// * Visits and winrates are empty if the game is over.
+    pub fn search_policy(
+        &self,
+        board: &mut Board,
+        temperature: Option<f64>,
+        control: &SearchControl,
+    ) -> (Option<usize>, SearchPolicy) {
+        if board.is_game_over() {
+            return (None, SearchPolicy::from_children([], 0));
+        }
+        let mut rng = self.new_rng();
+        let (root, playouts, _) = self.run(board, control, Instant::now(), &mut rng);
+        let temperature = temperature.unwrap_or(self.temperature);
+        let index = root.sample_child_index(temperature, &mut rng);
+        (root.child_move(index), root.policy(playouts as u64))
+    }
+}
+
+const DEFAULT_MARGIN_TIME: f64 = 0.002;
+const DEFAULT_CHECK_INTERVAL: usize = 100;
+impl Search for PuctSearch {
+    fn get_move(&self, board: &mut Board) -> Option<usize> {
+        if board.is_pass() {
+            return None;
+        }
+        self.search_policy(board, None, &SearchControl::default()).0
+    }
+
+    fn get_move_with_timeout(&self, board: &mut Board, timeout: Duration) -> Option<usize> {
+        if board.is_pass() {
+            return None;
+        }
+        let control = SearchControl {
+            timeout: Some(timeout),
+            ..Default::default()
+        };
+        self.search_policy(board, None, &control).0
+    }
+
+    fn get_search_score(&self, board: &mut Board) -> f64 {
+        self.search_score(board, &SearchControl::default())
+    }
+}
+
+impl ControlledSearch for PuctSearch {
+    fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult {
+        PuctSearch::search(self, board, control)
+    }
+}
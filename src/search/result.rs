@@ -0,0 +1,148 @@
+use pyo3::prelude::*;
+
+/// Everything one search found out about a position.
+#[pyclass(get_all, module = "rust_reversi")]
+#[derive(Clone, Debug, Default)]
+pub struct SearchResult {
+    /// Best move, or None if the player must pass or the game is over.
+    pub best_move: Option<usize>,
+    /// Score of the best move from the side to move's point of view.
+    pub score: f64,
+    /// Expected line of play starting with `best_move`. None stands for a pass.
+    pub pv: Vec<Option<usize>>,
+    /// Depth of the last completed iteration for AlphaBetaSearch,
+    /// deepest visited ply for the tree searches.
+    pub depth: usize,
+    /// Positions visited for AlphaBetaSearch, playouts for the tree searches.
+    pub nodes: u64,
+    pub elapsed_ms: f64,
+    /// Whether the timeout, the node budget or a stop request stopped the search early.
+    pub timed_out: bool,
+}
+
+fn option_repr<T: ToString>(value: Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "None".to_string(),
+    }
+}
+
+fn pv_repr(pv: &[Option<usize>]) -> String {
+    let moves = pv.iter().map(|&m| option_repr(m)).collect::<Vec<_>>();
+    format!("[{}]", moves.join(", "))
+}
+
+#[pymethods]
+impl SearchResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "SearchResult(best_move={}, score={}, pv={}, depth={}, nodes={}, elapsed_ms={:.3}, timed_out={})",
+            option_repr(self.best_move),
+            self.score,
+            pv_repr(&self.pv),
+            self.depth,
+            self.nodes,
+            self.elapsed_ms,
+            if self.timed_out { "True" } else { "False" },
+        )
+    }
+}
+
+/// Evaluation of one root move.
+#[pyclass(get_all, module = "rust_reversi")]
+#[derive(Clone, Debug)]
+pub struct MoveAnalysis {
+    #[pyo3(name = "move")]
+    pub pos: usize,
+    /// Score after playing the move from the side to move's point of view.
+    /// 0.5 for MctsSearch moves that were never visited.
+    pub score: f64,
+    /// Expected line of play starting with the move. None stands for a pass.
+    pub pv: Vec<Option<usize>>,
+    /// Visit count for MctsSearch, None for AlphaBetaSearch.
+    pub visits: Option<u64>,
+}
+
+#[pymethods]
+impl MoveAnalysis {
+    fn __repr__(&self) -> String {
+        format!(
+            "MoveAnalysis(move={}, score={}, pv={}, visits={})",
+            self.pos,
+            self.score,
+            pv_repr(&self.pv),
+            option_repr(self.visits),
+        )
+    }
+}
+
+/// Statistics of the root moves of a tree search, indexed by square with the pass at 64.
+#[pyclass(get_all, module = "rust_reversi")]
+#[derive(Clone, Debug)]
+pub struct SearchPolicy {
+    pub visits: Vec<u64>,
+    /// Share of the root visits. All zeros if nothing was visited.
+    pub policy: Vec<f64>,
+    /// Mean winrate after the move for the side to move. 0.5 for moves never visited.
+    pub winrates: Vec<f64>,
+    /// Winrates mapped to [-1, 1].
+    pub q_values: Vec<f64>,
+    /// Playouts run by this call.
+    pub nodes: u64,
+}
+
+impl SearchPolicy {
+    pub const SIZE: usize = 65;
+    pub const PASS: usize = 64;
+
+    /// Policy of a root whose children have the given move (None for the pass),
+    /// visit count and total value for the side to move at the root.
+    pub fn from_children(
+        children: impl IntoIterator<Item = (Option<usize>, u64, f64)>,
+        nodes: u64,
+    ) -> Self {
+        let mut visits = vec![0; Self::SIZE];
+        let mut winrates = vec![0.5; Self::SIZE];
+        for (pos, n_visits, w) in children {
+            let index = pos.unwrap_or(Self::PASS);
+            visits[index] = n_visits;
+            if n_visits > 0 {
+                winrates[index] = w / n_visits as f64;
+            }
+        }
+        let total = visits.iter().sum::<u64>();
+        let policy = visits
+            .iter()
+            .map(|&v| {
+                if total > 0 {
+                    v as f64 / total as f64
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let q_values = winrates.iter().map(|w| 2.0 * w - 1.0).collect();
+        SearchPolicy {
+            visits,
+            policy,
+            winrates,
+            q_values,
+            nodes,
+        }
+    }
+}
+
+#[pymethods]
+impl SearchPolicy {
+    fn __repr__(&self) -> String {
+        let moves = (0..Self::SIZE)
+            .filter(|&i| self.visits[i] > 0)
+            .map(|i| format!("{}: {}", i, self.visits[i]))
+            .collect::<Vec<_>>();
+        format!(
+            "SearchPolicy(visits={{{}}}, nodes={})",
+            moves.join(", "),
+            self.nodes
+        )
+    }
+}
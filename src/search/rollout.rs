@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use rand::{rngs::StdRng, Rng};
+use rust_reversi_core::board::Board;
+use rust_reversi_core::search::{Evaluator, WinrateEvaluator};
+
+use crate::board::{random_index, random_move};
+
+const CORNERS: [usize; 4] = [0, 7, 56, 63];
+
+/// How playout moves are chosen.
+#[derive(Debug, Clone, Default)]
+pub enum RolloutMoves {
+    #[default]
+    Random,
+    /// The best move for `evaluator`, or a random move with probability `epsilon`.
+    EpsilonGreedy {
+        evaluator: Arc<dyn Evaluator>,
+        epsilon: f64,
+    },
+    /// A random corner if one is legal, otherwise a random move.
+    CornerFirst,
+}
+
+/// Playout policy of MctsSearch.
+#[derive(Debug, Clone, Default)]
+pub struct RolloutPolicy {
+    pub moves: RolloutMoves,
+    /// Stop after this many plies and take the winrate of the evaluator as the result.
+    pub cutoff: Option<(usize, Arc<dyn WinrateEvaluator>)>,
+}
+
+impl RolloutPolicy {
+    /// Move to play, or None if the player must pass.
+    fn choose_move(&self, board: &mut Board, rng: &mut StdRng) -> Option<usize> {
+        match &self.moves {
+            RolloutMoves::Random => random_move(board, rng),
+            RolloutMoves::EpsilonGreedy { evaluator, epsilon } => {
+                if rng.gen_bool(*epsilon) {
+                    return random_move(board, rng);
+                }
+                let legal_moves = board.get_legal_moves_vec().to_vec();
+                // the child is scored for the opponent
+                legal_moves.into_iter().min_by_key(|&m| {
+                    let mut child = board.clone();
+                    child.do_move(m).unwrap();
+                    evaluator.evaluate(&mut child)
+                })
+            }
+            RolloutMoves::CornerFirst => {
+                let legal_moves = board.get_legal_moves_vec();
+                let corners = legal_moves
+                    .iter()
+                    .copied()
+                    .filter(|m| CORNERS.contains(m))
+                    .collect::<Vec<_>>();
+                if corners.is_empty() {
+                    random_move(board, rng)
+                } else {
+                    Some(corners[random_index(rng, corners.len())])
+                }
+            }
+        }
+    }
+
+    /// Play `board` out and return its value for the side to move.
+    pub fn play_out(&self, board: &Board, rng: &mut StdRng) -> f64 {
+        let mut board = board.clone();
+        let node_turn = board.get_turn();
+        let mut plies = 0;
+        while !board.is_game_over() {
+            if let Some((max_plies, evaluator)) = &self.cutoff {
+                if plies >= *max_plies {
+                    let winrate = evaluator.evaluate(&mut board);
+                    return if board.get_turn() == node_turn {
+                        winrate
+                    } else {
+                        1.0 - winrate
+                    };
+                }
+            }
+            match self.choose_move(&mut board, rng) {
+                Some(m) => board.do_move(m).unwrap(),
+                None => board.do_pass().unwrap(),
+            }
+            plies += 1;
+        }
+        match board.get_winner().unwrap() {
+            Some(winner) => {
+                if winner == node_turn {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            None => 0.5,
+        }
+    }
+}
@@ -0,0 +1,546 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_reversi_core::board::Board;
+use rust_reversi_core::search::{Search, WinrateEvaluator};
+
+use super::batch::BatchWinrateEvaluator;
+use super::control::{ControlledSearch, SearchControl};
+use super::result::{SearchPolicy, SearchResult};
+use crate::board::random_index;
+
+/// Value of a finished game for the side to move.
+fn terminal_score(board: &Board) -> f64 {
+    match (board.is_win(), board.is_lose()) {
+        (Ok(true), _) => 1.0,
+        (_, Ok(true)) => 0.0,
+        _ => 0.5,
+    }
+}
+
+struct ThunderNode {
+    board: Board,
+    w: f64,
+    n_visits: usize,
+    children: Option<Vec<ThunderNode>>,
+}
+
+impl ThunderNode {
+    fn new(board: Board) -> Self {
+        Self {
+            board,
+            w: 0.0,
+            n_visits: 0,
+            children: None,
+        }
+    }
+
+    /// Mean value for the side to move, 0.5 before the first visit.
+    fn winrate(&self) -> f64 {
+        if self.n_visits > 0 {
+            self.w / self.n_visits as f64
+        } else {
+            0.5
+        }
+    }
+
+    fn expand(&mut self) {
+        if let Some(children) = self.board.get_child_boards() {
+            self.children = Some(children.into_iter().map(ThunderNode::new).collect());
+        } else {
+            let mut board = self.board.clone();
+            board.do_pass().unwrap();
+            self.children = Some(vec![ThunderNode::new(board)]);
+        }
+    }
+
+    fn score_board(board: &mut Board, evaluator: &Arc<dyn WinrateEvaluator>) -> f64 {
+        if board.is_game_over() {
+            match (board.is_win(), board.is_lose()) {
+                (Ok(true), _) => return 1.0,
+                (_, Ok(true)) => return 0.0,
+                _ => return 0.5,
+            }
+        }
+        evaluator.evaluate(board)
+    }
+
+    fn select_child_index(&self, ctx: &mut ThunderContext) -> usize {
+        let children = self.children.as_ref().unwrap();
+        for (i, child) in children.iter().enumerate() {
+            if child.n_visits == 0 {
+                return i;
+            }
+        }
+        if ctx.rng.gen_bool(ctx.epsilon) {
+            return random_index(&mut ctx.rng, children.len());
+        }
+        let mut best_child_index = 0;
+        let mut best_thunder_score = f64::NEG_INFINITY;
+        for (i, child) in children.iter().enumerate() {
+            let thunder_score = 1.0 - child.w / child.n_visits as f64;
+            if thunder_score > best_thunder_score {
+                best_thunder_score = thunder_score;
+                best_child_index = i;
+            }
+        }
+        best_child_index
+    }
+
+    fn evaluate(&mut self, ctx: &mut ThunderContext) -> f64 {
+        if self.board.is_game_over() {
+            let value = match self.board.get_winner().unwrap() {
+                Some(winner) => {
+                    if winner == self.board.get_turn() {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                }
+                None => 0.5,
+            };
+            self.w += value;
+            self.n_visits += 1;
+            value
+        } else if self.children.is_none() {
+            let value = Self::score_board(&mut self.board, &ctx.evaluator);
+            self.w += value;
+            self.n_visits += 1;
+            self.expand();
+            value
+        } else {
+            let child_index = self.select_child_index(ctx);
+            let value = 1.0 - self.children.as_mut().unwrap()[child_index].evaluate(ctx);
+            self.w += value;
+            self.n_visits += 1;
+            value
+        }
+    }
+
+    /// Walk down to a leaf, adding a virtual loss to every node on the way
+    /// so that the next walks of the same batch spread over other leaves.
+    /// Returns the child indices leading to the leaf in `path`.
+    fn select_leaf(&mut self, path: &mut Vec<usize>, ctx: &mut ThunderContext) {
+        self.n_visits += 1;
+        self.w += VIRTUAL_LOSS;
+        if self.board.is_game_over() || self.children.is_none() {
+            return;
+        }
+        let child_index = self.select_child_index(ctx);
+        path.push(child_index);
+        self.children.as_mut().unwrap()[child_index].select_leaf(path, ctx);
+    }
+
+    /// Replace the virtual losses of `select_leaf` along `path` with the value of the leaf,
+    /// expanding it if needed.
+    fn backup(&mut self, path: &[usize], leaf_value: f64) -> f64 {
+        let value = match path.split_first() {
+            Some((&child_index, rest)) => {
+                1.0 - self.children.as_mut().unwrap()[child_index].backup(rest, leaf_value)
+            }
+            None => {
+                if self.children.is_none() && !self.board.is_game_over() {
+                    self.expand();
+                }
+                leaf_value
+            }
+        };
+        self.w += value - VIRTUAL_LOSS;
+        value
+    }
+
+    /// Add the statistics of `other`, a tree of the same board searched by another thread.
+    fn merge(&mut self, other: ThunderNode) {
+        self.w += other.w;
+        self.n_visits += other.n_visits;
+        match (&mut self.children, other.children) {
+            (Some(children), Some(other_children)) => {
+                for (child, other_child) in children.iter_mut().zip(other_children) {
+                    child.merge(other_child);
+                }
+            }
+            (children @ None, other_children) => *children = other_children,
+            _ => {}
+        }
+    }
+
+    fn node(&self, path: &[usize]) -> &ThunderNode {
+        path.iter()
+            .fold(self, |node, &i| &node.children.as_ref().unwrap()[i])
+    }
+
+    /// Run `n` playouts whose leaves are evaluated in one batch.
+    fn evaluate_batch(
+        &mut self,
+        n: usize,
+        batch_evaluator: &dyn BatchWinrateEvaluator,
+        ctx: &mut ThunderContext,
+    ) {
+        let paths = (0..n)
+            .map(|_| {
+                let mut path = Vec::new();
+                self.select_leaf(&mut path, ctx);
+                path
+            })
+            .collect::<Vec<_>>();
+        let leaves = paths
+            .iter()
+            .map(|path| &self.node(path).board)
+            .filter(|board| !board.is_game_over())
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut values = batch_evaluator.evaluate_batch(&leaves).into_iter();
+        for path in &paths {
+            let board = &self.node(path).board;
+            let value = if board.is_game_over() {
+                terminal_score(board)
+            } else {
+                values.next().unwrap_or(0.5)
+            };
+            self.backup(path, value);
+        }
+    }
+
+    fn best_child_index(&self) -> usize {
+        let mut best_child_index = 0;
+        let mut best_n_visits = 0;
+        for (i, child) in self.children.as_ref().unwrap().iter().enumerate() {
+            if child.n_visits > best_n_visits {
+                best_n_visits = child.n_visits;
+                best_child_index = i;
+            }
+        }
+        best_child_index
+    }
+
+    /// Move leading to the child at `index`. None for the pass child.
+    fn child_move(&self, index: usize) -> Option<usize> {
+        let mut board = self.board.clone();
+        if board.is_pass() {
+            None
+        } else {
+            Some(board.get_legal_moves_vec()[index])
+        }
+    }
+
+    /// Visits and winrates of the children.
+    fn policy(&self, nodes: u64) -> SearchPolicy {
+        let children = self.children.as_ref().unwrap().iter().enumerate();
+        SearchPolicy::from_children(
+            children.map(|(i, child)| {
+                let n_visits = child.n_visits as f64;
+                (
+                    self.child_move(i),
+                    child.n_visits as u64,
+                    n_visits - child.w,
+                )
+            }),
+            nodes,
+        )
+    }
+
+    /// Most visited line from this node.
+    fn pv(&self) -> Vec<Option<usize>> {
+        let mut pv = Vec::new();
+        let mut node = self;
+        while let Some(children) = &node.children {
+            let index = node.best_child_index();
+            if children[index].n_visits == 0 {
+                break;
+            }
+            pv.push(node.child_move(index));
+            node = &children[index];
+        }
+        pv
+    }
+
+    /// Deepest visited ply below this node.
+    fn depth(&self) -> usize {
+        match &self.children {
+            Some(children) => children
+                .iter()
+                .filter(|child| child.n_visits > 0)
+                .map(|child| child.depth() + 1)
+                .max()
+                .unwrap_or(0),
+            None => 0,
+        }
+    }
+}
+
+/// Value added to a node per visit while its leaf waits for a batch evaluation.
+const VIRTUAL_LOSS: f64 = 1.0;
+
+/// State shared by every node during one search.
+struct ThunderContext {
+    epsilon: f64,
+    evaluator: Arc<dyn WinrateEvaluator>,
+    rng: StdRng,
+}
+
+/// Thunder search: MCTS with an evaluator at the leaves and epsilon-greedy selection.
+/// # Note
+/// * Same algorithm as `rust_reversi_core::search::ThunderSearch`, with a seedable RNG.
+/// * With a batch evaluator, `batch_size` leaves are selected with virtual losses
+///   and evaluated together in one call.
+/// * With more than one thread, each thread grows its own tree with its share of
+///   the playouts and the trees are merged (root parallelization).
+#[derive(Debug)]
+pub struct ThunderSearch {
+    n_playouts: usize,
+    epsilon: f64,
+    threads: usize,
+    evaluator: Arc<dyn WinrateEvaluator>,
+    batch_evaluator: Option<Arc<dyn BatchWinrateEvaluator>>,
+    batch_size: usize,
+    margin_time: f64,
+    check_interval: usize,
+    rng: Mutex<StdRng>,
+}
+
+impl ThunderSearch {
+    /// Create a new ThunderSearch instance.
+    /// # Arguments
+    /// * `n_playouts` - The number of playouts to run.
+    /// * `epsilon` - The probability of selecting a random child.
+    /// * `evaluator` - The evaluator to evaluate the board.
+    /// * `seed` - Seed of the RNG. None seeds from the OS.
+    pub fn new(
+        n_playouts: usize,
+        epsilon: f64,
+        evaluator: Arc<dyn WinrateEvaluator>,
+        seed: Option<u64>,
+    ) -> Self {
+        Self {
+            n_playouts,
+            epsilon,
+            threads: 1,
+            evaluator,
+            batch_evaluator: None,
+            batch_size: 1,
+            margin_time: DEFAULT_MARGIN_TIME,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            rng: Mutex::new(match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            }),
+        }
+    }
+
+    /// Set the number of search threads.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Evaluate leaves `batch_size` at a time with `batch_evaluator`.
+    pub fn set_batch_evaluator(
+        &mut self,
+        batch_evaluator: Arc<dyn BatchWinrateEvaluator>,
+        batch_size: usize,
+    ) {
+        self.batch_evaluator = Some(batch_evaluator);
+        self.batch_size = batch_size.max(1);
+    }
+
+    fn new_context(&self) -> ThunderContext {
+        // each search draws its own seed, so a seeded instance gives the same sequence of moves
+        let seed = self.rng.lock().unwrap().gen();
+        ThunderContext {
+            epsilon: self.epsilon,
+            evaluator: self.evaluator.clone(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Expand the root and run the playouts, stopping early on timeout or stop.
+    /// Returns the root, the number of playouts run and the number of playouts planned.
+    /// # Note
+    /// * `control.max_nodes` overrides the number of playouts set at construction.
+    /// * With more than one thread, progress is reported for the tree of the calling thread.
+    fn run(
+        &self,
+        board: &Board,
+        control: &SearchControl,
+        start: Instant,
+    ) -> (ThunderNode, usize, usize) {
+        let n_playouts = control.max_nodes.map_or(self.n_playouts, |n| n as usize);
+        // contexts are drawn in order, so a seeded instance stays reproducible
+        let mut contexts = (0..self.threads)
+            .map(|_| self.new_context())
+            .collect::<Vec<_>>();
+        let share =
+            |i: usize| n_playouts / self.threads + usize::from(i < n_playouts % self.threads);
+        let new_root = || {
+            let mut root = ThunderNode::new(board.clone());
+            root.expand();
+            root
+        };
+        let mut root = new_root();
+        let mut ctx = contexts.remove(0);
+        if contexts.is_empty() {
+            let playouts = self.run_playouts(&mut root, &mut ctx, control, n_playouts, start);
+            return (root, playouts, n_playouts);
+        }
+        let helper_control = SearchControl {
+            progress: None,
+            ..*control
+        };
+        let playouts = thread::scope(|s| {
+            let helpers = contexts
+                .into_iter()
+                .enumerate()
+                .map(|(i, mut ctx)| {
+                    let helper_control = &helper_control;
+                    let new_root = &new_root;
+                    s.spawn(move || {
+                        let mut root = new_root();
+                        let playouts = self.run_playouts(
+                            &mut root,
+                            &mut ctx,
+                            helper_control,
+                            share(i + 1),
+                            start,
+                        );
+                        (root, playouts)
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut playouts = self.run_playouts(&mut root, &mut ctx, control, share(0), start);
+            for helper in helpers {
+                let (tree, helper_playouts) = helper.join().unwrap();
+                root.merge(tree);
+                playouts += helper_playouts;
+            }
+            playouts
+        });
+        (root, playouts, n_playouts)
+    }
+
+    /// Run `n_playouts` playouts from `root`, stopping early on timeout or stop.
+    /// Returns the number of playouts run.
+    fn run_playouts(
+        &self,
+        root: &mut ThunderNode,
+        ctx: &mut ThunderContext,
+        control: &SearchControl,
+        n_playouts: usize,
+        start: Instant,
+    ) -> usize {
+        let time_keeper = control.time_keeper(self.margin_time);
+        let mut playouts = 0;
+        while playouts < n_playouts {
+            let before = playouts;
+            match &self.batch_evaluator {
+                Some(batch_evaluator) => {
+                    let n = self.batch_size.min(n_playouts - playouts);
+                    root.evaluate_batch(n, batch_evaluator.as_ref(), ctx);
+                    playouts += n;
+                }
+                None => {
+                    root.evaluate(ctx);
+                    playouts += 1;
+                }
+            }
+            let interval = control.progress_interval;
+            if interval > 0 && playouts / interval > before / interval {
+                control.report(&self.make_result(root, playouts, n_playouts, start));
+            }
+            // a batch is slow enough to check after each one
+            if self.batch_evaluator.is_some() || before % self.check_interval == 0 {
+                let timed_out = time_keeper.as_ref().is_some_and(|t| t.is_timeout());
+                if timed_out || control.is_stopped() {
+                    break;
+                }
+            }
+        }
+        playouts
+    }
+
+    fn make_result(
+        &self,
+        root: &ThunderNode,
+        playouts: usize,
+        n_playouts: usize,
+        start: Instant,
+    ) -> SearchResult {
+        let index = root.best_child_index();
+        let best_child = &root.children.as_ref().unwrap()[index];
+        SearchResult {
+            best_move: root.child_move(index),
+            score: 1.0 - best_child.winrate(),
+            pv: root.pv(),
+            depth: root.depth(),
+            nodes: playouts as u64,
+            elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+            timed_out: playouts < n_playouts,
+        }
+    }
+
+    /// Search the board and report the principal variation and statistics.
+    pub fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult {
+        let start = Instant::now();
+        if board.is_game_over() {
+            return SearchResult {
+                score: terminal_score(board),
+                elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+                ..Default::default()
+            };
+        }
+        let (root, playouts, n_playouts) = self.run(board, control, start);
+        self.make_result(&root, playouts, n_playouts, start)
+    }
+
+    /// Winrate of the root for the side to move.
+    pub fn search_score(&self, board: &mut Board, control: &SearchControl) -> f64 {
+        if board.is_game_over() {
+            return terminal_score(board);
+        }
+        let (root, ..) = self.run(board, control, Instant::now());
+        root.winrate()
+    }
+
+    /// Visits and winrates of the root moves.
+    /// # Note
+    /// * Visits and winrates are empty if the game is over.
+    pub fn search_policy(&self, board: &mut Board, control: &SearchControl) -> SearchPolicy {
+        if board.is_game_over() {
+            return SearchPolicy::from_children([], 0);
+        }
+        let (root, playouts, _) = self.run(board, control, Instant::now());
+        root.policy(playouts as u64)
+    }
+}
+
+const DEFAULT_MARGIN_TIME: f64 = 0.0011;
+const DEFAULT_CHECK_INTERVAL: usize = 100;
+impl Search for ThunderSearch {
+    fn get_move(&self, board: &mut Board) -> Option<usize> {
+        if board.is_pass() {
+            return None;
+        }
+        self.search(board, &SearchControl::default()).best_move
+    }
+
+    fn get_move_with_timeout(&self, board: &mut Board, timeout: Duration) -> Option<usize> {
+        if board.is_pass() {
+            return None;
+        }
+        let control = SearchControl {
+            timeout: Some(timeout),
+            ..Default::default()
+        };
+        self.search(board, &control).best_move
+    }
+
+    fn get_search_score(&self, board: &mut Board) -> f64 {
+        self.search_score(board, &SearchControl::default())
+    }
+}
+
+impl ControlledSearch for ThunderSearch {
+    fn search(&self, board: &mut Board, control: &SearchControl) -> SearchResult {
+        ThunderSearch::search(self, board, control)
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// How the stored score relates to the true score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// The true score is at least the stored score.
+    Lower,
+    /// The true score is at most the stored score.
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TtEntry {
+    pub score: i32,
+    pub depth: usize,
+    pub bound: Bound,
+    pub best_move: Option<usize>,
+}
+
+const NO_MOVE: u64 = 64;
+const ENTRY_BYTES: usize = 16;
+
+impl TtEntry {
+    // layout: score 32 bits, depth 8, bound 2, move 7, generation 8
+    fn pack(&self, generation: u8) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 0,
+            Bound::Lower => 1,
+            Bound::Upper => 2,
+        };
+        (self.score as u32 as u64)
+            | ((self.depth.min(u8::MAX as usize) as u64) << 32)
+            | (bound << 40)
+            | (self.best_move.map_or(NO_MOVE, |m| m as u64) << 42)
+            | ((generation as u64) << 49)
+    }
+
+    fn unpack(data: u64) -> (Self, u8) {
+        let bound = match (data >> 40) & 0b11 {
+            0 => Bound::Exact,
+            1 => Bound::Lower,
+            _ => Bound::Upper,
+        };
+        let best_move = (data >> 42) & 0x7F;
+        let entry = TtEntry {
+            score: data as u32 as i32,
+            depth: ((data >> 32) & 0xFF) as usize,
+            bound,
+            best_move: (best_move != NO_MOVE).then_some(best_move as usize),
+        };
+        (entry, (data >> 49) as u8)
+    }
+}
+
+/// Fixed-size transposition table keyed by Zobrist hash.
+/// # Note
+/// * Entries are two atomics with the key stored xor the data, so the table can be
+///   shared between threads without locks; torn writes fail the key check.
+/// * Entries written before the last `new_search` with `keep = false` are ignored.
+/// * The entries are allocated by the first store, so an unused table costs nothing.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    entries: OnceLock<Vec<[AtomicU64; 2]>>,
+    n_entries: usize,
+    generation: AtomicU8,
+}
+
+impl TranspositionTable {
+    /// Create a table of at most `size_mb` megabytes, rounded down to a power of two entries.
+    pub fn new(size_mb: usize) -> Self {
+        let max_entries = (size_mb * 1024 * 1024 / ENTRY_BYTES).max(1);
+        Self {
+            entries: OnceLock::new(),
+            n_entries: 1 << max_entries.ilog2(),
+            generation: AtomicU8::new(1),
+        }
+    }
+
+    fn entries(&self) -> &[[AtomicU64; 2]] {
+        self.entries.get_or_init(|| {
+            (0..self.n_entries)
+                .map(|_| [AtomicU64::new(0), AtomicU64::new(0)])
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn index(&self, key: u64) -> usize {
+        key as usize & (self.n_entries - 1)
+    }
+
+    /// Start a new search. Unless `keep` is set, earlier entries are no longer returned.
+    pub fn new_search(&self, keep: bool) {
+        if !keep {
+            let generation = self.generation.load(Ordering::Relaxed);
+            if generation == u8::MAX {
+                // 0 is the generation of empty entries
+                self.clear();
+                self.generation.store(1, Ordering::Relaxed);
+            } else {
+                self.generation.store(generation + 1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn probe(&self, key: u64) -> Option<TtEntry> {
+        let [stored_key, stored_data] = &self.entries.get()?[self.index(key)];
+        let data = stored_data.load(Ordering::Relaxed);
+        if stored_key.load(Ordering::Relaxed) ^ data != key {
+            return None;
+        }
+        let (entry, generation) = TtEntry::unpack(data);
+        (generation == self.generation.load(Ordering::Relaxed)).then_some(entry)
+    }
+
+    pub fn store(&self, key: u64, entry: TtEntry) {
+        let [stored_key, stored_data] = &self.entries()[self.index(key)];
+        let generation = self.generation.load(Ordering::Relaxed);
+        let old_data = stored_data.load(Ordering::Relaxed);
+        let (old_entry, old_generation) = TtEntry::unpack(old_data);
+        // keep a deeper result for the same position from the current search
+        if stored_key.load(Ordering::Relaxed) ^ old_data == key
+            && old_generation == generation
+            && old_entry.depth > entry.depth
+        {
+            return;
+        }
+        let data = entry.pack(generation);
+        stored_key.store(key ^ data, Ordering::Relaxed);
+        stored_data.store(data, Ordering::Relaxed);
+    }
+
+    /// Remove every entry.
+    pub fn clear(&self) {
+        let Some(entries) = self.entries.get() else {
+            return;
+        };
+        for [key, data] in entries {
+            key.store(0, Ordering::Relaxed);
+            data.store(0, Ordering::Relaxed);
+        }
+    }
+}
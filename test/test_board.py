@@ -1,3 +1,6 @@
+import copy
+import pickle
+import pytest
 from rust_reversi import Board, Turn, Color
 
 
@@ -7,6 +10,12 @@ def test_turn():
     assert Turn.BLACK != Turn.WHITE
     assert str(Turn.BLACK) == "Black"
     assert str(Turn.WHITE) == "White"
+    assert repr(Turn.BLACK) == "Turn.BLACK"
+    assert int(Turn.BLACK) == 0
+    assert int(Turn.WHITE) == 1
+    assert len({Turn.BLACK, Turn.WHITE, Turn.BLACK}) == 2
+    for turn in (Turn.BLACK, Turn.WHITE):
+        assert pickle.loads(pickle.dumps(turn)) == turn
 
 
 def test_color():
@@ -19,6 +28,11 @@ def test_color():
     assert str(Color.EMPTY) == "Empty"
     assert str(Color.BLACK) == "Black"
     assert str(Color.WHITE) == "White"
+    assert repr(Color.EMPTY) == "Color.EMPTY"
+    assert [int(c) for c in (Color.EMPTY, Color.BLACK, Color.WHITE)] == [0, 1, 2]
+    for color in (Color.EMPTY, Color.BLACK, Color.WHITE):
+        assert pickle.loads(pickle.dumps(color)) == color
+    assert len({Color.EMPTY, Color.BLACK, Color.WHITE, Color.EMPTY}) == 3
 
 
 def test_init():
@@ -259,3 +273,392 @@ def test_pass_game_over():
     board.set_board_str(board_str, Turn.BLACK)
     assert board.is_game_over()
     assert board.is_black_win()
+
+
+def test_undo_redo():
+    board = Board()
+    initial = board.get_board()
+    board.do_move(19)
+    after_first = board.get_board()
+    board.do_move(18)
+    assert board.history() == [19, 18]
+    assert board.move_count() == 2
+
+    board.undo()
+    assert board.get_board() == after_first
+    assert board.history() == [19]
+    board.undo()
+    assert board.get_board() == initial
+    assert board.move_count() == 0
+    assert not board.can_undo()
+    with pytest.raises(ValueError):
+        board.undo()
+
+    board.redo()
+    board.redo()
+    assert board.history() == [19, 18]
+    assert not board.can_redo()
+    with pytest.raises(ValueError):
+        board.redo()
+
+    board.undo()
+    board.do_move(34)
+    assert board.history() == [19, 34]
+    assert not board.can_redo()
+
+
+def test_undo_pass():
+    board = Board()
+    board_str = "{}{}{}{}{}{}{}{}".format(
+        "--------",
+        "--------",
+        "--OOO---",
+        "---OOO--",
+        "--OOOO--",
+        "--OO----",
+        "---O----",
+        "---X----",
+    )
+    board.set_board_str(board_str, Turn.WHITE)
+    board.do_pass()
+    assert board.history() == [None]
+    assert board.get_turn() == Turn.BLACK
+    board.undo()
+    assert board.get_turn() == Turn.WHITE
+    board.redo()
+    assert board.get_turn() == Turn.BLACK
+
+    board.set_board_str(board_str, Turn.WHITE)
+    assert board.history() == []
+    assert not board.can_redo()
+
+
+def test_pos_str():
+    assert Board.pos_to_str(0) == "a1"
+    assert Board.pos_to_str(37) == "f5"
+    assert Board.pos_to_str(63) == "h8"
+    assert Board.str_to_pos("f5") == 37
+    assert Board.str_to_pos("F5") == 37
+    for pos in range(64):
+        assert Board.str_to_pos(Board.pos_to_str(pos)) == pos
+    with pytest.raises(ValueError):
+        Board.pos_to_str(64)
+    with pytest.raises(ValueError):
+        Board.str_to_pos("i9")
+
+
+def test_transcript():
+    board = Board()
+    board.do_move_str("f5")
+    board.do_move_str("d6")
+    board.do_move_str("c3")
+    assert board.history() == [37, 43, 18]
+    assert board.to_transcript() == "f5d6c3"
+
+    replayed = Board.from_transcript("f5d6c3")
+    assert replayed.get_board() == board.get_board()
+    assert Board.from_transcript("F5 D6 C3").get_board() == board.get_board()
+
+    with pytest.raises(ValueError, match="illegal move a1 at move 2"):
+        Board.from_transcript("f5a1")
+    with pytest.raises(ValueError):
+        Board.from_transcript("f5d")
+    with pytest.raises(ValueError):
+        board.do_move_str("a1")
+
+
+def test_transcript_random_games():
+    for _ in range(20):
+        board = Board()
+        while not board.is_game_over():
+            if board.is_pass():
+                board.do_pass()
+            else:
+                board.do_move(board.get_random_move())
+        replayed = Board.from_transcript(board.to_transcript())
+        assert replayed.get_board() == board.get_board()
+        assert replayed.history() == board.history()
+
+
+def test_eq_hash():
+    board1 = Board.from_transcript("f5d6c3")
+    board2 = Board.from_transcript("f5d6c3")
+    assert board1 == board2
+    assert hash(board1) == hash(board2)
+    assert board1.hash() == board2.hash()
+    assert board1 != Board()
+    assert not (Board() != Board())
+    assert board1.hash() != Board().hash()
+    assert board1 != "f5d6c3"
+
+    # transpositions are equal regardless of history
+    board3 = Board.from_transcript("c4e3f6")
+    board3.set_board(*board1.get_board())
+    assert board3 == board1
+    assert len({board1, board2, board3, Board()}) == 2
+
+    player, opponent, _ = board1.get_board()
+    flipped = Board()
+    flipped.set_board(player, opponent, Turn.BLACK)
+    assert flipped != board1
+    assert flipped.hash() != board1.hash()
+
+
+def test_pickle_copy():
+    board = Board.from_transcript("f5d6c3d3")
+    board.undo()
+    restored = pickle.loads(pickle.dumps(board))
+    assert restored == board
+    assert restored.history() == board.history()
+    assert restored.can_redo()
+    restored.redo()
+    assert restored.to_transcript() == "f5d6c3d3"
+
+    shallow = copy.copy(board)
+    deep = copy.deepcopy(board)
+    board.do_move_str("d3")
+    assert shallow.to_transcript() == "f5d6c3"
+    assert deep.to_transcript() == "f5d6c3"
+
+
+def test_symmetries():
+    board = Board.from_transcript("f5d6c3d3c4")
+    vec = board.get_board_vec_turn()
+    expected = {
+        1: lambda r, c: (c, 7 - r),
+        2: lambda r, c: (7 - r, 7 - c),
+        3: lambda r, c: (7 - c, r),
+        4: lambda r, c: (r, 7 - c),
+        5: lambda r, c: (7 - r, c),
+        6: lambda r, c: (c, r),
+        7: lambda r, c: (7 - c, 7 - r),
+    }
+    symmetries = board.symmetries()
+    assert len(symmetries) == 8
+    assert symmetries[0] == board
+    for t, f in expected.items():
+        transformed_vec = symmetries[t].get_board_vec_turn()
+        for pos in range(64):
+            r, c = f(pos // 8, pos % 8)
+            assert Board.transform_pos(pos, t) == r * 8 + c
+            assert transformed_vec[r * 8 + c] == vec[pos]
+        inverse = Board.inverse_transform(t)
+        assert symmetries[t].transform(inverse) == board
+    assert board.rotate90() == symmetries[1]
+    assert board.flip_horizontal() == symmetries[4]
+    assert board.flip_vertical() == symmetries[5]
+    assert board.flip_diagonal() == symmetries[6]
+    with pytest.raises(ValueError):
+        board.transform(8)
+
+
+def test_canonical():
+    board = Board.from_transcript("f5d6c3d3c4")
+    canonical, t = board.canonical()
+    assert board.transform(t) == canonical
+    for sym in board.symmetries():
+        sym_canonical, _ = sym.canonical()
+        assert sym_canonical == canonical
+        assert sym_canonical.get_board()[:2] <= sym.get_board()[:2]
+
+    # legal moves map through the same transform
+    legal = sorted(Board.transform_pos(m, t) for m in board.get_legal_moves_vec())
+    assert legal == canonical.get_legal_moves_vec()
+
+
+def test_get_flips():
+    board = Board()
+    board_str = "{}{}{}{}{}{}{}{}".format(
+        "--------",
+        "--------",
+        "--OOO---",
+        "---OXX--",
+        "--OOXX--",
+        "--OX----",
+        "--------",
+        "--------",
+    )
+    board.set_board_str(board_str, Turn.WHITE)
+    assert board.get_flips_vec(21) == [28]
+    assert board.get_flips(21) == 1 << (63 - 28)
+    before = board.get_board()
+    peeked = board.peek_move(21)
+    assert board.get_board() == before
+    board.do_move(21)
+    assert peeked == board
+    with pytest.raises(ValueError):
+        board.get_flips(0)
+    with pytest.raises(ValueError):
+        board.get_flips(64)
+
+
+def test_get_flips_random_games():
+    for _ in range(20):
+        board = Board()
+        while not board.is_game_over():
+            if board.is_pass():
+                board.do_pass()
+                continue
+            move = board.get_random_move()
+            player, opponent, _ = board.get_board()
+            flips = board.get_flips(move)
+            board.do_move(move)
+            new_player, new_opponent, _ = board.get_board()
+            assert new_player == opponent ^ flips
+            assert new_opponent == player | flips | (1 << (63 - move))
+
+
+def bits_to_set(bitboard):
+    return {i for i in range(64) if bitboard & (1 << (63 - i))}
+
+
+def neighbor_set(pos):
+    r, c = pos // 8, pos % 8
+    return {
+        (r + dr) * 8 + c + dc
+        for dr in (-1, 0, 1)
+        for dc in (-1, 0, 1)
+        if (dr, dc) != (0, 0) and 0 <= r + dr < 8 and 0 <= c + dc < 8
+    }
+
+
+def test_stable_discs():
+    board = Board()
+    board_str = "{}{}{}{}{}{}{}{}".format(
+        "XXO-----",
+        "XX------",
+        "--------",
+        "---OX---",
+        "---XO---",
+        "--------",
+        "--------",
+        "-------O",
+    )
+    board.set_board_str(board_str, Turn.BLACK)
+    player_stable, opponent_stable = board.get_stable_discs()
+    assert bits_to_set(player_stable) == {0, 1, 8}
+    assert bits_to_set(opponent_stable) == {63}
+    player_edge, opponent_edge = board.get_edge_stable_discs()
+    assert bits_to_set(player_edge) == {0, 1, 8}
+    assert bits_to_set(opponent_edge) == {63}
+
+    board.set_board_str("X" * 32 + "O" * 32, Turn.BLACK)
+    player_stable, opponent_stable = board.get_stable_discs()
+    assert player_stable == 0xFFFFFFFF00000000
+    assert opponent_stable == 0x00000000FFFFFFFF
+
+
+def test_stable_discs_never_flip():
+    for _ in range(20):
+        board = Board()
+        stable = (0, 0)
+        while not board.is_game_over():
+            player, opponent, turn = board.get_board()
+            player_stable, opponent_stable = board.get_stable_discs()
+            assert player_stable & player == player_stable
+            assert opponent_stable & opponent == opponent_stable
+            black_stable, white_stable = (
+                (player_stable, opponent_stable)
+                if turn == Turn.BLACK
+                else (opponent_stable, player_stable)
+            )
+            # discs once stable must stay stable
+            assert black_stable & stable[0] == stable[0]
+            assert white_stable & stable[1] == stable[1]
+            stable = (black_stable, white_stable)
+            if board.is_pass():
+                board.do_pass()
+            else:
+                board.do_move(board.get_random_move())
+
+
+def test_positional_features():
+    for _ in range(10):
+        board = Board()
+        for _ in range(30):
+            if board.is_game_over():
+                break
+            if board.is_pass():
+                board.do_pass()
+                continue
+            board.do_move(board.get_random_move())
+            player, opponent, _ = board.get_board()
+            player_set, opponent_set = bits_to_set(player), bits_to_set(opponent)
+            empty_set = set(range(64)) - player_set - opponent_set
+
+            player_frontier, opponent_frontier = board.get_frontier_discs()
+            assert bits_to_set(player_frontier) == {
+                p for p in player_set if neighbor_set(p) & empty_set
+            }
+            assert bits_to_set(opponent_frontier) == {
+                p for p in opponent_set if neighbor_set(p) & empty_set
+            }
+
+            player_pm, opponent_pm = board.get_potential_mobility()
+            assert bits_to_set(player_pm) == {
+                p for p in empty_set if neighbor_set(p) & opponent_set
+            }
+            assert bits_to_set(opponent_pm) == {
+                p for p in empty_set if neighbor_set(p) & player_set
+            }
+
+            regions = board.get_empty_regions()
+            assert set().union(*map(bits_to_set, regions)) == empty_set
+            for region in regions:
+                region_set = bits_to_set(region)
+                for p in region_set:
+                    assert not (neighbor_set(p) & (empty_set - region_set))
+            odd = board.get_odd_empty_regions()
+            assert odd == sum(r for r in regions if bin(r).count("1") % 2 == 1)
+
+
+def test_square_discs():
+    board = Board()
+    board_str = "{}{}{}{}{}{}{}{}".format(
+        "XO-----O",
+        "OX----X-",
+        "--------",
+        "---OX---",
+        "---XO---",
+        "--------",
+        "-O------",
+        "X------X",
+    )
+    board.set_board_str(board_str, Turn.BLACK)
+    corner_p, corner_o = board.get_corner_discs()
+    assert bits_to_set(corner_p) == {0, 56, 63}
+    assert bits_to_set(corner_o) == {7}
+    x_p, x_o = board.get_x_square_discs()
+    assert bits_to_set(x_p) == {9, 14}
+    assert bits_to_set(x_o) == {49}
+    c_p, c_o = board.get_c_square_discs()
+    assert bits_to_set(c_p) == set()
+    assert bits_to_set(c_o) == {1, 8}
+
+
+def test_random_move_seed():
+    board = Board()
+    for _ in range(4):
+        board.do_move(board.get_random_move())
+    moves = [board.get_random_move(seed=42) for _ in range(10)]
+    assert len(set(moves)) == 1
+    assert moves[0] in board.get_legal_moves_vec()
+
+
+def test_set_rng_seed():
+    def play(board):
+        moves = []
+        while not board.is_game_over():
+            if board.is_pass():
+                board.do_pass()
+            else:
+                move = board.get_random_move()
+                moves.append(move)
+                board.do_move(move)
+        return moves
+
+    board1 = Board()
+    board1.set_rng_seed(7)
+    board2 = Board()
+    board2.set_rng_seed(7)
+    assert play(board1) == play(board2)
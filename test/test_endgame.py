@@ -0,0 +1,134 @@
+import random
+import threading
+import time
+
+import pytest
+from rust_reversi import Board, EndgameSolver, Turn
+
+
+def final_score(board: Board) -> int:
+    player, opponent, _ = board.get_board()
+    p = bin(player).count("1")
+    o = bin(opponent).count("1")
+    empties = 64 - p - o
+    if p > o:
+        return p - o + empties
+    if p < o:
+        return p - o - empties
+    return 0
+
+
+def minimax(board: Board) -> int:
+    if board.is_game_over():
+        return final_score(board)
+    if board.is_pass():
+        child = board.clone()
+        child.do_pass()
+        return -minimax(child)
+    best = -65
+    for move in board.get_legal_moves_vec():
+        child = board.clone()
+        child.do_move(move)
+        best = max(best, -minimax(child))
+    return best
+
+
+def random_board(rng: random.Random, n_empties: int) -> Board:
+    board = Board()
+    while not board.is_game_over() and 64 - board.piece_sum() > n_empties:
+        if board.is_pass():
+            board.do_pass()
+        else:
+            board.do_move(rng.choice(board.get_legal_moves_vec()))
+    return board
+
+
+def test_solve_matches_minimax():
+    rng = random.Random(0)
+    solver = EndgameSolver()
+    for _ in range(50):
+        board = random_board(rng, rng.randint(1, 7))
+        scores = {}
+        for move in board.get_legal_moves_vec():
+            child = board.clone()
+            child.do_move(move)
+            scores[move] = -minimax(child)
+        score, best_moves = solver.solve(board)
+        assert score == minimax(board)
+        if scores:
+            assert best_moves == sorted(m for m, s in scores.items() if s == score)
+        else:
+            assert best_moves == []
+
+
+def test_solve_win_loss():
+    rng = random.Random(1)
+    solver = EndgameSolver()
+    for _ in range(20):
+        board = random_board(rng, rng.randint(4, 12))
+        score, best_moves = solver.solve(board)
+        result, wld_moves = solver.solve_win_loss(board)
+        assert result == (score > 0) - (score < 0)
+        assert set(best_moves) <= set(wld_moves)
+
+
+def test_get_move():
+    rng = random.Random(2)
+    solver = EndgameSolver()
+    board = random_board(rng, 10)
+    while board.is_pass():
+        board = random_board(rng, 10)
+    _, best_moves = solver.solve(board)
+    assert solver.get_move(board) == best_moves[0]
+
+
+def test_solve_pass_and_game_over():
+    solver = EndgameSolver()
+    board = Board()
+    board.set_board_str("-O" + "X" * 62, Turn.WHITE)
+    assert board.is_pass()
+    score, best_moves = solver.solve(board)
+    assert score == -64
+    assert best_moves == []
+    assert solver.get_move(board) is None
+
+    board.set_board_str("X" * 63 + "-", Turn.WHITE)
+    assert board.is_game_over()
+    assert solver.solve(board) == (-64, [])
+    assert solver.solve_win_loss(board) == (-1, [])
+
+
+def test_solve_limits():
+    rng = random.Random(3)
+    solver = EndgameSolver()
+    board = random_board(rng, 16)
+    while board.is_game_over():
+        board = random_board(rng, 16)
+    with pytest.raises(TimeoutError):
+        solver.solve(board, max_nodes=1)
+    with pytest.raises(TimeoutError):
+        solver.solve_win_loss(board, max_nodes=1)
+    with pytest.raises(TimeoutError):
+        solver.get_move(board, max_nodes=1)
+    assert solver.solve(board, timeout_ms=60000, max_nodes=10**9) == solver.solve(board)
+
+
+def test_solve_stop():
+    rng = random.Random(4)
+    solver = EndgameSolver()
+    board = random_board(rng, 30)
+    errors = []
+
+    def run():
+        try:
+            solver.solve(board)
+        except TimeoutError as e:
+            errors.append(e)
+
+    thread = threading.Thread(target=run)
+    thread.start()
+    time.sleep(0.1)
+    solver.stop()
+    thread.join(10)
+    assert not thread.is_alive()
+    assert len(errors) == 1
@@ -0,0 +1,191 @@
+import random
+import pytest
+from rust_reversi import (
+    AlphaBetaSearch,
+    Board,
+    BoardBatch,
+    OthelloEnv,
+    PieceEvaluator,
+    Turn,
+)
+
+PASS = 64
+
+
+def random_actions(batch: BoardBatch) -> list:
+    actions = []
+    for mask in batch.legal_moves_mask():
+        legal = [i for i, legal in enumerate(mask) if legal]
+        actions.append(random.choice(legal) if legal else 0)
+    return actions
+
+
+def test_batch_init():
+    batch = BoardBatch(4)
+    assert len(batch) == 4
+    assert BoardBatch.__module__ == "rust_reversi"
+    assert batch.dones() == [False] * 4
+    assert batch.rewards() == [0.0] * 4
+    assert batch.turns() == [Turn.BLACK] * 4
+    board = Board()
+    for mask in batch.legal_moves_mask():
+        assert mask == board.get_legal_moves_tf() + [False]
+
+
+def test_batch_step_matches_board():
+    n = 8
+    batch = BoardBatch(n)
+    boards = [Board() for _ in range(n)]
+    while not all(batch.dones()):
+        actions = random_actions(batch)
+        batch.step(actions)
+        for board, action in zip(boards, actions):
+            if board.is_game_over():
+                continue
+            board.do_move(action)
+            if board.is_pass() and not board.is_game_over():
+                board.do_pass()
+        for i, board in enumerate(boards):
+            assert batch.get_board(i) == board
+    for reward_black, reward_white, board in zip(
+        batch.rewards(), batch.rewards(Turn.WHITE), boards
+    ):
+        winner = board.get_winner()
+        if winner is None:
+            assert reward_black == reward_white == 0.0
+        else:
+            assert reward_black == (1.0 if winner == Turn.BLACK else -1.0)
+            assert reward_white == -reward_black
+
+
+def test_batch_invalid_step():
+    batch = BoardBatch(2)
+    with pytest.raises(ValueError):
+        batch.step([19])
+    with pytest.raises(ValueError, match="board 1"):
+        batch.step([19, 0])
+    # nothing was applied
+    assert batch.get_boards() == [Board(), Board()]
+    with pytest.raises(ValueError):
+        batch.step([19, PASS])
+
+
+def test_batch_pass_and_reset():
+    board = Board()
+    board_str = "{}{}{}{}{}{}{}{}".format(
+        "--------",
+        "--------",
+        "--OOO---",
+        "---OOO--",
+        "--OOOO--",
+        "--OO----",
+        "---O----",
+        "---X----",
+    )
+    board.set_board_str(board_str, Turn.WHITE)
+    batch = BoardBatch(2, auto_pass=False)
+    batch.set_board(1, board)
+    assert batch.legal_moves_mask()[1][PASS]
+    batch.step([19, PASS])
+    assert batch.turns() == [Turn.WHITE, Turn.BLACK]
+
+    batch.reset([1])
+    assert batch.get_board(1) == Board()
+    assert batch.get_board(0) != Board()
+    batch.reset()
+    assert batch.get_boards() == [Board(), Board()]
+    with pytest.raises(ValueError):
+        batch.reset([2])
+
+
+def play_random_episode(env, rng: random.Random):
+    observation, info = env.reset()
+    trajectory = [info["action_mask"]]
+    terminated = False
+    while not terminated:
+        mask = env.action_masks()
+        assert mask == info["action_mask"]
+        action = rng.choice([i for i, legal in enumerate(mask) if legal])
+        observation, reward, terminated, truncated, info = env.step(action)
+        assert not truncated
+        trajectory.append(action)
+    return trajectory, reward
+
+
+def test_env_episode():
+    env = OthelloEnv(seed=0)
+    observation, info = env.reset(seed=0)
+    if hasattr(observation, "dtype"):
+        np = pytest.importorskip("numpy")
+        assert observation.dtype == np.float32
+        observation = observation.tolist()
+    assert [len(plane) for plane in observation] == [8, 8, 8]
+    assert info["agent_turn"] == Turn.BLACK
+    legal_moves = [cell == 1.0 for row in observation[2] for cell in row]
+    assert legal_moves == Board().get_legal_moves_tf()
+
+    trajectory, reward = play_random_episode(env, random.Random(0))
+    board = env.board
+    assert board.is_game_over()
+    winner = board.get_winner()
+    assert reward == (0.0 if winner is None else 1.0 if winner == Turn.BLACK else -1.0)
+    with pytest.raises(ValueError):
+        env.step(0)
+
+
+def test_env_seed_and_opponent():
+    env_a = OthelloEnv(agent_turn=None, reward="disc_diff", seed=7)
+    env_b = OthelloEnv(agent_turn=None, reward="disc_diff", seed=7)
+    for _ in range(3):
+        trajectory, reward = play_random_episode(env_a, random.Random(2))
+        assert (trajectory, reward) == play_random_episode(env_b, random.Random(2))
+        assert abs(reward) == abs(env_a.board.diff_piece_num())
+
+    search = AlphaBetaSearch(PieceEvaluator(), 1, 1 << 10)
+    env = OthelloEnv(opponent=search, agent_turn=Turn.WHITE)
+    observation, info = env.reset()
+    assert info["turn"] == Turn.WHITE
+    assert env.board.piece_sum() == 5
+    with pytest.raises(ValueError):
+        env.step(0)
+    with pytest.raises(ValueError):
+        OthelloEnv(reward="score")
+
+
+def test_env_spaces():
+    pytest.importorskip("gymnasium")
+    env = OthelloEnv(seed=0)
+    assert env.action_space.n == 65
+    assert env.observation_space.shape == (3, 8, 8)
+    assert env.action_space is env.action_space
+    assert env.observation_space is env.observation_space
+    observation, _ = env.reset()
+    assert env.observation_space.contains(observation)
+
+
+def test_gym_env():
+    gymnasium = pytest.importorskip("gymnasium")
+    pytest.importorskip("numpy")
+    from gymnasium.utils.env_checker import check_env
+    from rust_reversi.gym import ENV_ID, OthelloGymEnv
+
+    env = OthelloGymEnv(agent_turn=None)
+    assert isinstance(env, gymnasium.Env)
+    check_env(env)
+
+    env = gymnasium.make(ENV_ID, reward="disc_diff", render_mode="ansi")
+    observation, info = env.reset(seed=0)
+    assert env.observation_space.contains(observation)
+    assert isinstance(env.render(), str)
+    terminated = False
+    while not terminated:
+        action = info["action_mask"].index(True)
+        observation, reward, terminated, truncated, info = env.step(action)
+        assert not info["illegal_action"]
+    assert abs(reward) == abs(env.unwrapped.env.board.diff_piece_num())
+
+    env = OthelloGymEnv(illegal_action_reward=-5.0)
+    observation, info = env.reset(seed=0)
+    action = info["action_mask"].index(False)
+    _, reward, terminated, _, info = env.step(action)
+    assert (reward, terminated, info["illegal_action"]) == (-5.0, True, True)
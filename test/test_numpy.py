@@ -0,0 +1,46 @@
+import pytest
+from rust_reversi import Board
+
+np = pytest.importorskip("numpy")
+
+if not hasattr(Board, "get_board_planes"):
+    pytest.skip("built without the numpy feature", allow_module_level=True)
+
+
+def test_board_planes():
+    board = Board()
+    planes = board.get_board_planes()
+    assert planes.shape == (3, 8, 8)
+    assert planes.dtype == np.float32
+    matrix = np.array(board.get_board_matrix(), dtype=np.float32)
+    assert (planes[0] == matrix[0]).all()
+    assert (planes[1] == matrix[1]).all()
+    assert (planes[2].reshape(64) == np.array(board.get_legal_moves_tf())).all()
+    assert (board.get_legal_moves_array() == np.array(board.get_legal_moves_tf())).all()
+
+
+def test_history_planes():
+    board = Board()
+    initial = board.get_board_planes()
+    board.do_move(19)
+    board.do_move(18)
+    planes = board.get_board_planes(history=3)
+    assert planes.shape == (9, 8, 8)
+    # two plies ago the same side was to move
+    assert (planes[5] == initial[0]).all()
+    assert (planes[6] == initial[1]).all()
+    # one ply ago the colors are swapped
+    previous = Board.from_transcript("d3")
+    previous_planes = previous.get_board_planes()
+    assert (planes[3] == previous_planes[1]).all()
+    assert (planes[4] == previous_planes[0]).all()
+    assert not planes[7:].any()
+
+
+def test_stack_board_planes():
+    boards = [Board(), Board.from_transcript("f5"), Board.from_transcript("f5d6")]
+    stacked = Board.stack_board_planes(boards, history=1)
+    assert stacked.shape == (3, 5, 8, 8)
+    for i, board in enumerate(boards):
+        assert (stacked[i] == board.get_board_planes(history=1)).all()
+    assert Board.stack_board_planes([]).shape == (0, 3, 8, 8)
@@ -0,0 +1,211 @@
+from rust_reversi import (
+    Board,
+    PolicyValueEvaluator,
+    PuctSearch,
+    Turn,
+)
+import pytest
+
+
+class PiecePolicyValueEvaluator(PolicyValueEvaluator):
+    """Prior proportional to the pieces flipped, value from the piece difference."""
+
+    def __init__(self):
+        super().set_py_evaluator(self)
+        self.calls = 0
+
+    def evaluate(self, board):
+        self.calls += 1
+        policy = [0.0] * 65
+        for move in board.get_legal_moves_vec():
+            child = board.clone()
+            child.do_move(move)
+            policy[move] = child.opponent_piece_num() - board.player_piece_num()
+        diff = board.player_piece_num() - board.opponent_piece_num()
+        return policy, 0.5 + diff / 128
+
+
+class BatchPolicyValueEvaluator(PiecePolicyValueEvaluator):
+    def __init__(self):
+        super().__init__()
+        self.batch_sizes = []
+
+    def evaluate_batch(self, boards):
+        self.batch_sizes.append(len(boards))
+        evaluations = [self.evaluate(board) for board in boards]
+        return [p for p, _ in evaluations], [v for _, v in evaluations]
+
+
+def play_game(search) -> list:
+    board = Board()
+    moves = []
+    while not board.is_game_over():
+        if board.is_pass():
+            board.do_pass()
+        else:
+            move = search.get_move(board)
+            moves.append(move)
+            board.do_move(move)
+    return moves
+
+
+def test_puct_default_evaluator():
+    board = Board()
+    policy, value = PolicyValueEvaluator().evaluate(board)
+    assert len(policy) == 65
+    assert sum(policy) == pytest.approx(1.0)
+    assert all(policy[m] == 0.25 for m in board.get_legal_moves_vec())
+    assert 0.0 <= value <= 1.0
+
+    search = PuctSearch(PolicyValueEvaluator(), 100)
+    assert search.get_move(board) in board.get_legal_moves_vec()
+
+
+def test_puct_search_result():
+    board = Board()
+    evaluator = PiecePolicyValueEvaluator()
+    search = PuctSearch(evaluator, 200)
+    result = search.search(board)
+    assert result.best_move in board.get_legal_moves_vec()
+    assert result.pv[0] == result.best_move
+    assert result.nodes == 200
+    assert 0.0 <= result.score <= 1.0
+    assert not result.timed_out
+    # the root and one leaf per playout
+    assert evaluator.calls <= 201
+
+
+def test_puct_policy():
+    board = Board()
+    search = PuctSearch(PiecePolicyValueEvaluator(), 100, seed=0)
+    move, policy = search.get_move_and_policy(board)
+    assert len(policy) == 65
+    assert sum(policy) == pytest.approx(1.0)
+    assert policy[move] == max(policy)
+    legal_moves = board.get_legal_moves_vec()
+    assert all(p == 0.0 for i, p in enumerate(policy) if i not in legal_moves)
+
+    _, policy = search.get_move_and_policy(board, n_playouts=10)
+    assert sum(round(p * 10) for p in policy) == 10
+
+    board.set_board_str("-O" + "X" * 62, Turn.WHITE)
+    move, policy = search.get_move_and_policy(board)
+    assert move is None
+    assert policy[64] == 1.0
+
+
+def test_puct_temperature():
+    board = Board()
+    greedy = PuctSearch(PolicyValueEvaluator(), 50, seed=0)
+    _, policy = greedy.get_move_and_policy(board)
+    best = max(range(65), key=lambda i: policy[i])
+    assert all(greedy.get_move_and_policy(board, 0.0)[0] == best for _ in range(5))
+
+    sampling = PuctSearch(PolicyValueEvaluator(), 50, temperature=1.0, seed=0)
+    moves = {sampling.get_move(board) for _ in range(30)}
+    assert len(moves) > 1
+    assert moves <= set(board.get_legal_moves_vec())
+
+
+def test_puct_seed():
+    moves1 = play_game(
+        PuctSearch(PolicyValueEvaluator(), 20, dirichlet_alpha=0.3, temperature=1.0, seed=5)
+    )
+    moves2 = play_game(
+        PuctSearch(PolicyValueEvaluator(), 20, dirichlet_alpha=0.3, temperature=1.0, seed=5)
+    )
+    assert moves1 == moves2
+
+
+def test_puct_dirichlet_noise():
+    board = Board()
+    policies = set()
+    search = PuctSearch(PolicyValueEvaluator(), 40, dirichlet_alpha=0.3, seed=0)
+    for _ in range(5):
+        _, policy = search.get_move_and_policy(board)
+        policies.add(tuple(policy))
+    assert len(policies) > 1
+
+    search = PuctSearch(PolicyValueEvaluator(), 40, seed=0)
+    policies = {tuple(search.get_move_and_policy(board)[1]) for _ in range(3)}
+    assert len(policies) == 1
+
+
+def test_puct_batch_evaluator():
+    board = Board()
+    evaluator = BatchPolicyValueEvaluator()
+    search = PuctSearch(evaluator, 100, batch_size=8)
+    result = search.search(board)
+    assert result.nodes == 100
+    assert result.best_move in board.get_legal_moves_vec()
+    assert max(evaluator.batch_sizes) <= 8
+    assert max(evaluator.batch_sizes) > 1
+
+
+def test_puct_evaluator_errors():
+    board = Board()
+
+    class ShortPolicy(PolicyValueEvaluator):
+        def __init__(self):
+            super().set_py_evaluator(self)
+
+        def evaluate(self, board):
+            return [1.0] * 10, 0.5
+
+    with pytest.raises(ValueError, match="64 or 65 values"):
+        PuctSearch(ShortPolicy(), 10).get_move(board)
+
+    class LongPolicy(ShortPolicy):
+        def evaluate(self, board):
+            return [1.0] * 100, 0.5
+
+    with pytest.raises(ValueError, match="64 or 65 values"):
+        PuctSearch(LongPolicy(), 10).get_move(board)
+
+    class Failing(PiecePolicyValueEvaluator):
+        def evaluate(self, board):
+            raise ZeroDivisionError("policy bug")
+
+    with pytest.raises(ZeroDivisionError, match="policy bug"):
+        PuctSearch(Failing(), 10).search(board)
+
+
+def test_puct_game_over():
+    board = Board()
+    board.set_board_str("X" * 64, Turn.BLACK)
+    search = PuctSearch(PolicyValueEvaluator(), 10)
+    result = search.search(board)
+    assert result.best_move is None
+    assert result.score == 1.0
+    move, policy = search.get_move_and_policy(board)
+    assert move is None
+    assert policy == [0.0] * 65
+
+
+def test_puct_get_policy():
+    board = Board()
+    search = PuctSearch(PiecePolicyValueEvaluator(), 100, seed=0)
+    policy = search.get_policy(board)
+    assert policy.nodes == 100
+    assert sum(policy.visits) == 100
+    assert sum(policy.policy) == pytest.approx(1.0)
+    for move in board.get_legal_moves_vec():
+        assert 0.0 <= policy.winrates[move] <= 1.0
+    _, distribution = search.get_move_and_policy(board)
+    assert distribution == policy.policy
+
+
+def test_puct_invalid_parameters():
+    evaluator = PolicyValueEvaluator()
+    with pytest.raises(ValueError, match="c_puct"):
+        PuctSearch(evaluator, 10, c_puct=-1.0)
+    with pytest.raises(ValueError, match="dirichlet_alpha"):
+        PuctSearch(evaluator, 10, dirichlet_alpha=-1.0)
+    with pytest.raises(ValueError, match="dirichlet_alpha"):
+        PuctSearch(evaluator, 10, dirichlet_alpha=0.0)
+    with pytest.raises(ValueError, match="dirichlet_epsilon"):
+        PuctSearch(evaluator, 10, dirichlet_alpha=0.3, dirichlet_epsilon=1.5)
+    with pytest.raises(ValueError, match="temperature"):
+        PuctSearch(evaluator, 10, temperature=-1.0)
+    with pytest.raises(ValueError, match="temperature"):
+        PuctSearch(evaluator, 10).get_move_and_policy(Board(), -1.0)
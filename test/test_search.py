@@ -1,5 +1,18 @@
-from rust_reversi import Arena
+from rust_reversi import (
+    AlphaBetaSearch,
+    Arena,
+    Board,
+    Evaluator,
+    MctsSearch,
+    PieceEvaluator,
+    RolloutPolicy,
+    ThunderSearch,
+    Turn,
+    WinrateEvaluator,
+)
 import sys
+import threading
+import time
 import os
 import pytest
 
@@ -117,3 +130,758 @@ def test_custom_vs_matrix():
     win_ratio = abs((wins1 - wins2) / N_GAMES)
 
     assert win_ratio < 0.1  # sometimes it fails
+
+
+def play_seeded_game(search) -> list:
+    board = Board()
+    moves = []
+    while not board.is_game_over():
+        if board.is_pass():
+            board.do_pass()
+        else:
+            move = search.get_move(board)
+            moves.append(move)
+            board.do_move(move)
+    return moves
+
+
+class ConstWinrateEvaluator(WinrateEvaluator):
+    def __init__(self):
+        super().set_py_evaluator(self)
+
+    def evaluate(self, board):
+        return 0.5
+
+
+def test_mcts_seed():
+    moves1 = play_seeded_game(MctsSearch(50, 1.0, 3, seed=1))
+    moves2 = play_seeded_game(MctsSearch(50, 1.0, 3, seed=1))
+    assert moves1 == moves2
+
+
+def test_thunder_seed():
+    moves1 = play_seeded_game(ThunderSearch(ConstWinrateEvaluator(), 30, 0.1, seed=3))
+    moves2 = play_seeded_game(ThunderSearch(ConstWinrateEvaluator(), 30, 0.1, seed=3))
+    assert moves1 == moves2
+
+
+def test_thunder_invalid_epsilon():
+    with pytest.raises(ValueError, match="epsilon"):
+        ThunderSearch(ConstWinrateEvaluator(), 10, 1.5)
+    with pytest.raises(ValueError, match="epsilon"):
+        ThunderSearch(ConstWinrateEvaluator(), 10, -0.1)
+
+
+def test_search_pass():
+    board = Board()
+    board.set_board_str("-O" + "X" * 62, Turn.WHITE)
+    assert board.is_pass()
+    assert MctsSearch(10, 1.0, 3).get_move(board) is None
+    assert ThunderSearch(ConstWinrateEvaluator(), 10, 0.1).get_move(board) is None
+
+
+def check_pv(board, result):
+    assert result.pv[0] == result.best_move
+    board = board.clone()
+    for move in result.pv:
+        if move is None:
+            board.do_pass()
+        else:
+            board.do_move(move)
+
+
+def test_alpha_beta_search_result():
+    board = Board()
+    search = AlphaBetaSearch(PieceEvaluator(), 3, 1 << 10)
+    result = search.search(board)
+    assert result.best_move == search.get_move(board)
+    assert result.score == search.get_search_score(board)
+    assert result.depth == 3
+    assert len(result.pv) == 4
+    assert result.nodes > 0
+    assert result.elapsed_ms >= 0
+    assert not result.timed_out
+    check_pv(board, result)
+
+
+def test_alpha_beta_search_timeout():
+    board = Board()
+    search = AlphaBetaSearch(PieceEvaluator(), 60, 1 << 10)
+    result = search.search(board, timeout_ms=50)
+    assert result.timed_out
+    assert 0 < result.depth < 60
+    assert result.best_move in board.get_legal_moves_vec()
+    check_pv(board, result)
+
+
+def test_tree_search_result():
+    board = Board()
+    for search in [
+        MctsSearch(300, 1.0, 3, seed=0),
+        ThunderSearch(ConstWinrateEvaluator(), 300, 0.1, seed=0),
+    ]:
+        result = search.search(board)
+        assert result.best_move in board.get_legal_moves_vec()
+        assert 0.0 <= result.score <= 1.0
+        assert result.nodes == 300
+        assert result.depth >= len(result.pv) > 0
+        assert not result.timed_out
+        check_pv(board, result)
+
+
+def test_search_result_pass():
+    board = Board()
+    board.set_board_str("-O" + "X" * 62, Turn.WHITE)
+    result = AlphaBetaSearch(PieceEvaluator(), 2, 1 << 10).search(board)
+    assert result.best_move is None
+    assert result.pv == [None, 0]
+    assert result.score == -(1 << 10)
+    result = MctsSearch(10, 1.0, 3).search(board)
+    assert result.best_move is None
+    assert result.pv[0] is None
+
+
+def test_alpha_beta_analyze():
+    board = Board()
+    board.do_move(19)
+    search = AlphaBetaSearch(PieceEvaluator(), 2, 1 << 10)
+    analysis = search.analyze(board)
+    assert sorted(a.move for a in analysis) == sorted(board.get_legal_moves_vec())
+    scores = [a.score for a in analysis]
+    assert scores == sorted(scores, reverse=True)
+    result = search.search(board)
+    assert analysis[0].move == result.best_move
+    assert analysis[0].score == result.score
+    # each move is searched as deep as search() searches the children
+    child_search = AlphaBetaSearch(PieceEvaluator(), 1, 1 << 10)
+    for a in analysis:
+        assert a.visits is None
+        assert a.pv[0] == a.move
+        child = board.clone()
+        child.do_move(a.move)
+        assert a.score == -child_search.search(child).score
+
+    top = search.analyze(board, multi_pv=2)
+    assert [(a.move, a.score) for a in top] == [(a.move, a.score) for a in analysis[:2]]
+
+
+def test_mcts_analyze():
+    board = Board()
+    search = MctsSearch(400, 1.0, 3, seed=0)
+    analysis = search.analyze(board)
+    assert sorted(a.move for a in analysis) == sorted(board.get_legal_moves_vec())
+    assert sum(a.visits for a in analysis) == 400
+    visits = [a.visits for a in analysis]
+    assert visits == sorted(visits, reverse=True)
+    for a in analysis:
+        assert 0.0 <= a.score <= 1.0
+        assert a.pv[0] == a.move
+    assert len(search.analyze(board, multi_pv=1)) == 1
+
+
+def test_tree_search_no_playouts():
+    board = Board()
+    search = MctsSearch(0, 1.0, 3)
+    assert search.get_search_score(board) == 0.5
+    for a in search.analyze(board):
+        assert a.visits == 0
+        assert a.score == 0.5
+    assert ThunderSearch(ConstWinrateEvaluator(), 0, 0.1).get_search_score(board) == 0.5
+    assert search.search(board).score == 0.5
+
+
+def test_analyze_pass():
+    board = Board()
+    board.set_board_str("-O" + "X" * 62, Turn.WHITE)
+    assert AlphaBetaSearch(PieceEvaluator(), 2, 1 << 10).analyze(board) == []
+    assert MctsSearch(10, 1.0, 3).analyze(board) == []
+
+
+def test_alpha_beta_tt_same_result():
+    board = Board()
+    for _ in range(10):
+        board.do_move(board.get_random_move(seed=3))
+    with_tt = AlphaBetaSearch(PieceEvaluator(), 6, 1 << 10)
+    without_tt = AlphaBetaSearch(PieceEvaluator(), 6, 1 << 10, tt_size_mb=0)
+    result = with_tt.search(board)
+    expected = without_tt.search(board)
+    assert result.score == expected.score
+    assert result.nodes <= expected.nodes
+    check_pv(board, result)
+    assert [(a.move, a.score) for a in with_tt.analyze(board)] == [
+        (a.move, a.score) for a in without_tt.analyze(board)
+    ]
+
+
+def test_alpha_beta_persist_tt():
+    board = Board()
+    search = AlphaBetaSearch(PieceEvaluator(), 4, 1 << 10)
+    assert not search.persist_tt
+    first = search.search(board)
+    assert search.search(board).nodes == first.nodes
+
+    search.persist_tt = True
+    assert search.persist_tt
+    second = search.search(board)
+    assert second.score == first.score
+    assert second.nodes < first.nodes
+
+    search.clear_tt()
+    assert search.search(board).nodes == first.nodes
+    assert AlphaBetaSearch(PieceEvaluator(), 4, 1 << 10, persist_tt=True).persist_tt
+
+
+def test_alpha_beta_tt_allocated_lazily():
+    # 64 GB if the tables were allocated up front
+    searches = [
+        AlphaBetaSearch(PieceEvaluator(), 2, 1 << 10, tt_size_mb=1024) for _ in range(64)
+    ]
+    assert len(searches) == 64
+    search = AlphaBetaSearch(PieceEvaluator(), 4, 1 << 10)
+    search.clear_tt()
+    board = Board()
+    expected = AlphaBetaSearch(PieceEvaluator(), 4, 1 << 10, tt_size_mb=0).search(board)
+    assert search.search(board).score == expected.score
+
+
+def root_visits(search, board):
+    return sum(a.visits for a in search.analyze(board))
+
+
+def test_mcts_reuse_tree():
+    board = Board()
+    search = MctsSearch(500, 1.0, 3, seed=0)
+    assert not search.reuse_tree
+    search.search(board)
+    assert root_visits(search, board) == 500
+
+    search = MctsSearch(500, 1.0, 3, seed=0, reuse_tree=True)
+    assert search.reuse_tree
+    result = search.search(board)
+    # own move and opponent reply
+    board.do_move(result.pv[0])
+    board.do_move(result.pv[1])
+    assert root_visits(search, board) > 500
+    # the same board again
+    assert root_visits(search, board) > 1000
+
+    # a board not reached from the tree
+    board.do_move(board.get_legal_moves_vec()[0])
+    board.do_move(board.get_legal_moves_vec()[0])
+    board.do_move(board.get_legal_moves_vec()[0])
+    assert root_visits(search, board) == 500
+
+    search.clear_tree()
+    assert root_visits(search, board) == 500
+    search.reuse_tree = False
+    assert not search.reuse_tree
+    assert root_visits(search, board) == 500
+
+
+class PyPieceEvaluator(Evaluator):
+    def __init__(self):
+        super().set_py_evaluator(self)
+
+    def evaluate(self, board):
+        return board.player_piece_num() - board.opponent_piece_num()
+
+
+def test_alpha_beta_threads():
+    board = Board()
+    for _ in range(8):
+        board.do_move(board.get_random_move(seed=5))
+    single = AlphaBetaSearch(PieceEvaluator(), 5, 1 << 10).search(board)
+    parallel = AlphaBetaSearch(PieceEvaluator(), 5, 1 << 10, threads=4).search(board)
+    assert parallel.score == single.score
+    assert parallel.depth == single.depth
+    assert not parallel.timed_out
+    check_pv(board, parallel)
+
+    search = AlphaBetaSearch(PieceEvaluator(), 60, 1 << 10, threads=4)
+    result = search.search(board, timeout_ms=100)
+    assert result.timed_out
+    assert result.best_move in board.get_legal_moves_vec()
+
+
+def test_alpha_beta_without_limits_goes_straight_to_max_depth():
+    board = Board()
+    for _ in range(8):
+        board.do_move(board.get_random_move(seed=5))
+    search = AlphaBetaSearch(PieceEvaluator(), 5, 1 << 10, tt_size_mb=0)
+    straight = search.search(board)
+    iterative = search.search(board, max_nodes=10**9)
+    assert straight.depth == iterative.depth == 5
+    assert straight.score == iterative.score
+    # iterative deepening also searches depths 0 to 4
+    assert straight.nodes < iterative.nodes
+    assert search.get_search_score(board) == straight.score
+
+
+def test_alpha_beta_threads_py_evaluator():
+    board = Board()
+    expected = AlphaBetaSearch(PieceEvaluator(), 3, 1 << 10).search(board)
+    result = AlphaBetaSearch(PyPieceEvaluator(), 3, 1 << 10, threads=2).search(board)
+    assert result.score == expected.score
+
+
+def test_search_releases_gil():
+    board = Board()
+    searches = [
+        AlphaBetaSearch(PieceEvaluator(), 60, 1 << 10),
+        ThunderSearch(WinrateEvaluator(), 1 << 30, 0.1),
+        MctsSearch(1 << 30, 1.0, 3),
+    ]
+    for search in searches:
+        thread = threading.Thread(target=search.search, args=(board, 300))
+        counter = 0
+        thread.start()
+        while thread.is_alive():
+            time.sleep(0.01)
+            counter += 1
+        thread.join()
+        # a search holding the GIL would block every wakeup until it returns
+        assert counter >= 10
+
+
+def test_concurrent_searches_py_evaluator():
+    board = Board()
+    expected = AlphaBetaSearch(PieceEvaluator(), 3, 1 << 10).search(board).score
+    scores = []
+
+    def run():
+        search = AlphaBetaSearch(PyPieceEvaluator(), 3, 1 << 10)
+        scores.append(search.search(board.clone()).score)
+
+    threads = [threading.Thread(target=run) for _ in range(4)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    assert scores == [expected] * 4
+
+
+def test_start_search_stop():
+    board = Board()
+    for search in [
+        AlphaBetaSearch(PieceEvaluator(), 60, 1 << 10),
+        ThunderSearch(WinrateEvaluator(), 1 << 30, 0.1),
+        MctsSearch(1 << 30, 1.0, 3),
+    ]:
+        handle = search.start_search(board)
+        time.sleep(0.05)
+        assert not handle.is_done()
+        handle.stop()
+        result = handle.result()
+        assert handle.is_done()
+        assert result.timed_out
+        assert result.best_move in board.get_legal_moves_vec()
+        assert handle.result().best_move == result.best_move
+
+
+def test_start_search_result():
+    board = Board()
+    search = MctsSearch(300, 1.0, 3, seed=0)
+    handle = search.start_search(board)
+    result = handle.result()
+    assert not result.timed_out
+    assert result.nodes == 300
+    assert result.best_move == MctsSearch(300, 1.0, 3, seed=0).get_move(board)
+
+
+def test_stop_running_search():
+    board = Board()
+    search = AlphaBetaSearch(PieceEvaluator(), 60, 1 << 10)
+    results = []
+    thread = threading.Thread(target=lambda: results.append(search.search(board)))
+    thread.start()
+    time.sleep(0.05)
+    search.stop()
+    thread.join(timeout=5)
+    assert not thread.is_alive()
+    assert results[0].timed_out
+    assert results[0].best_move in board.get_legal_moves_vec()
+
+
+def test_search_callback():
+    board = Board()
+    depths = []
+    result = AlphaBetaSearch(PieceEvaluator(), 4, 1 << 10).search(
+        board, callback=lambda r: depths.append(r.depth)
+    )
+    assert depths == [0, 1, 2, 3, 4]
+    assert result.depth == 4
+
+    playouts = []
+    MctsSearch(1000, 1.0, 3).search(
+        board, callback=lambda r: playouts.append(r.nodes), callback_interval=250
+    )
+    assert playouts == [250, 500, 750, 1000]
+
+
+def test_search_callback_exception():
+    board = Board()
+
+    def callback(result):
+        raise KeyError("stop here")
+
+    search = AlphaBetaSearch(PieceEvaluator(), 60, 1 << 10)
+    with pytest.raises(KeyError):
+        search.search(board, callback=callback)
+    handle = MctsSearch(1 << 30, 1.0, 3).start_search(board, callback=callback)
+    with pytest.raises(KeyError):
+        handle.result()
+
+
+def test_alpha_beta_node_budget():
+    board = Board()
+    search = AlphaBetaSearch(PieceEvaluator(), 60, 1 << 10)
+    result = search.search(board, max_nodes=5000)
+    assert result.timed_out
+    assert 0 < result.depth < 60
+    assert result.nodes <= 5000 + 64
+    assert result.best_move in board.get_legal_moves_vec()
+    check_pv(board, result)
+    assert search.search(board, max_nodes=5000).pv == result.pv
+    assert search.get_move_with_nodes(board, 5000) == result.best_move
+
+    unlimited = AlphaBetaSearch(PieceEvaluator(), 3, 1 << 10).search(board, max_nodes=10**9)
+    assert not unlimited.timed_out
+    assert unlimited.depth == 3
+
+
+def test_alpha_beta_tiny_limits():
+    board = Board()
+    legal_moves = board.get_legal_moves_vec()
+    search = AlphaBetaSearch(PieceEvaluator(), 6, 1 << 10)
+    for max_nodes in range(4):
+        assert search.get_move_with_nodes(board, max_nodes) in legal_moves
+        result = search.search(board, max_nodes=max_nodes)
+        assert result.timed_out
+        assert result.best_move in legal_moves
+        assert result.pv[0] == result.best_move
+    assert search.get_move_with_timeout(board, 0) in legal_moves
+    assert search.search(board, timeout_ms=0).best_move in legal_moves
+
+    handle = AlphaBetaSearch(PieceEvaluator(), 60, 1 << 10).start_search(board)
+    handle.stop()
+    assert handle.result().best_move in legal_moves
+
+
+def test_tree_search_playout_budget():
+    board = Board()
+    for make_search in [
+        lambda: MctsSearch(10**9, 1.0, 3, seed=1),
+        lambda: ThunderSearch(WinrateEvaluator(), 10**9, 0.1, seed=1),
+    ]:
+        result = make_search().search(board, n_playouts=200)
+        assert result.nodes == 200
+        assert not result.timed_out
+        assert make_search().get_move_with_playouts(board, 200) == result.best_move
+
+
+class FailingEvaluator(Evaluator):
+    def __init__(self):
+        super().set_py_evaluator(self)
+        self.calls = 0
+        self.fail_after = 0
+
+    def evaluate(self, board):
+        self.calls += 1
+        if self.calls > self.fail_after:
+            raise ZeroDivisionError("evaluator bug")
+        return 0
+
+
+class WrongTypeWinrateEvaluator(WinrateEvaluator):
+    def __init__(self):
+        super().set_py_evaluator(self)
+
+    def evaluate(self, board):
+        return "not a float"
+
+
+def test_py_evaluator_exception():
+    board = Board()
+    evaluator = FailingEvaluator()
+    evaluator.fail_after = 100
+    search = AlphaBetaSearch(evaluator, 60, 1 << 10)
+    with pytest.raises(ZeroDivisionError, match="evaluator bug"):
+        search.get_move(board)
+    with pytest.raises(ZeroDivisionError):
+        search.get_search_score(board)
+    with pytest.raises(ZeroDivisionError):
+        search.analyze(board)
+    with pytest.raises(ZeroDivisionError):
+        search.start_search(board).result()
+    threaded = AlphaBetaSearch(evaluator, 60, 1 << 10, threads=4)
+    with pytest.raises(ZeroDivisionError):
+        threaded.search(board)
+
+    thunder = ThunderSearch(WrongTypeWinrateEvaluator(), 100, 0.1)
+    with pytest.raises(TypeError):
+        thunder.get_move(board)
+    with pytest.raises(TypeError):
+        # the base method calls back into the Python override
+        WinrateEvaluator.evaluate(WrongTypeWinrateEvaluator(), board)
+
+
+def test_py_evaluator_exception_traceback():
+    board = Board()
+    search = AlphaBetaSearch(FailingEvaluator(), 3, 1 << 10)
+    with pytest.raises(ZeroDivisionError) as excinfo:
+        search.get_move(board)
+    tb = excinfo.value.__traceback__
+    while tb.tb_next is not None:
+        tb = tb.tb_next
+    assert tb.tb_frame.f_code.co_name == "evaluate"
+
+
+def test_search_after_py_evaluator_exception():
+    board = Board()
+    evaluator = FailingEvaluator()
+    evaluator.fail_after = 10
+    search = AlphaBetaSearch(evaluator, 2, 1 << 10)
+    with pytest.raises(ZeroDivisionError):
+        search.get_move(board)
+    evaluator.fail_after = 10**9
+    assert search.get_move(board) in board.get_legal_moves_vec()
+
+
+class BatchPieceEvaluator(Evaluator):
+    def __init__(self):
+        super().set_py_evaluator(self)
+        self.batch_sizes = []
+
+    def evaluate(self, board):
+        return board.player_piece_num() - board.opponent_piece_num()
+
+    def evaluate_batch(self, boards):
+        self.batch_sizes.append(len(boards))
+        return [self.evaluate(board) for board in boards]
+
+
+class BatchWinrateEvaluator(WinrateEvaluator):
+    def __init__(self):
+        super().set_py_evaluator(self)
+        self.batch_sizes = []
+        self.result_length = None
+
+    def evaluate(self, board):
+        return 0.5
+
+    def evaluate_batch(self, boards):
+        self.batch_sizes.append(len(boards))
+        n = len(boards) if self.result_length is None else self.result_length
+        return [(board.player_piece_num() + 1) / 66 for board in boards[:n]] + [0.5] * (
+            n - len(boards)
+        )
+
+
+def test_alpha_beta_batch_evaluator():
+    board = Board()
+    for move in [19, 18, 17]:
+        board.do_move(move)
+    expected = AlphaBetaSearch(PieceEvaluator(), 4, 1 << 10).search(board)
+    evaluator = BatchPieceEvaluator()
+    result = AlphaBetaSearch(evaluator, 4, 1 << 10).search(board)
+    assert result.score == expected.score
+    assert result.best_move == expected.best_move
+    assert evaluator.batch_sizes
+    assert max(evaluator.batch_sizes) > 1
+
+
+def test_thunder_batch_evaluator():
+    board = Board()
+    evaluator = BatchWinrateEvaluator()
+    search = ThunderSearch(evaluator, 200, 0.1, seed=0, batch_size=8)
+    result = search.search(board)
+    assert result.nodes == 200
+    assert result.best_move in board.get_legal_moves_vec()
+    assert max(evaluator.batch_sizes) <= 8
+    # virtual losses spread the playouts of a batch over different leaves
+    assert max(evaluator.batch_sizes) > 1
+    calls = len(evaluator.batch_sizes)
+    search.search(board, n_playouts=100)
+    assert len(evaluator.batch_sizes) - calls <= 100 // 8 + 1
+
+
+def test_batch_evaluator_ndarray():
+    np = pytest.importorskip("numpy")
+
+    class NdarrayEvaluator(BatchWinrateEvaluator):
+        def evaluate_batch(self, boards):
+            return np.array(super().evaluate_batch(boards))
+
+    board = Board()
+    search = ThunderSearch(NdarrayEvaluator(), 100, 0.1, seed=0)
+    assert search.get_move(board) in board.get_legal_moves_vec()
+
+
+def test_batch_evaluator_errors():
+    board = Board()
+    evaluator = BatchWinrateEvaluator()
+    evaluator.result_length = 1
+    search = ThunderSearch(evaluator, 100, 0.1)
+    with pytest.raises(ValueError, match="evaluate_batch returned 1 values"):
+        search.get_move(board)
+
+    class FailingBatchEvaluator(BatchPieceEvaluator):
+        def evaluate_batch(self, boards):
+            raise ZeroDivisionError("batch bug")
+
+    search = AlphaBetaSearch(FailingBatchEvaluator(), 4, 1 << 10)
+    with pytest.raises(ZeroDivisionError, match="batch bug"):
+        search.get_move(board)
+
+
+def check_policy(board, policy, nodes):
+    assert len(policy.visits) == len(policy.policy) == 65
+    assert len(policy.winrates) == len(policy.q_values) == 65
+    assert policy.nodes == nodes
+    legal_moves = board.get_legal_moves_vec()
+    for i in range(65):
+        if i in legal_moves:
+            assert policy.visits[i] > 0
+            assert 0.0 <= policy.winrates[i] <= 1.0
+            assert policy.q_values[i] == pytest.approx(2 * policy.winrates[i] - 1)
+        else:
+            assert policy.visits[i] == 0
+            assert policy.policy[i] == 0.0
+            assert policy.winrates[i] == 0.5
+            assert policy.q_values[i] == 0.0
+    assert sum(policy.policy) == pytest.approx(1.0)
+    total = sum(policy.visits)
+    assert all(p == v / total for p, v in zip(policy.policy, policy.visits))
+
+
+def test_mcts_get_policy():
+    board = Board()
+    search = MctsSearch(400, 1.0, 3, seed=0)
+    policy = search.get_policy(board)
+    check_policy(board, policy, 400)
+    assert sum(policy.visits) == 400
+    best = max(range(65), key=lambda i: policy.visits[i])
+    assert MctsSearch(400, 1.0, 3, seed=0).search(board).best_move == best
+    check_policy(board, search.get_policy(board, n_playouts=50), 50)
+
+
+def test_thunder_get_policy():
+    board = Board()
+    search = ThunderSearch(WinrateEvaluator(), 200, 0.1, seed=0)
+    policy = search.get_policy(board)
+    check_policy(board, policy, 200)
+    best = max(range(65), key=lambda i: policy.visits[i])
+    same_seed = ThunderSearch(WinrateEvaluator(), 200, 0.1, seed=0)
+    assert same_seed.search(board).best_move == best
+
+
+def test_get_policy_pass_and_game_over():
+    board = Board()
+    board.set_board_str("-O" + "X" * 62, Turn.WHITE)
+    policy = MctsSearch(10, 1.0, 3).get_policy(board)
+    assert policy.visits[64] == sum(policy.visits) == 10
+    assert policy.policy[64] == 1.0
+
+    board.set_board_str("X" * 64, Turn.BLACK)
+    policy = ThunderSearch(ConstWinrateEvaluator(), 10, 0.1).get_policy(board)
+    assert policy.visits == [0] * 65
+    assert policy.policy == [0.0] * 65
+    assert policy.winrates == [0.5] * 65
+    assert policy.q_values == [0.0] * 65
+
+
+def test_tree_search_threads():
+    board = Board()
+    for search in [
+        MctsSearch(1000, 1.0, 3, seed=0, threads=4),
+        ThunderSearch(WinrateEvaluator(), 1000, 0.1, seed=0, threads=4),
+    ]:
+        result = search.search(board)
+        assert result.nodes == 1000
+        assert result.best_move in board.get_legal_moves_vec()
+        check_pv(board, result)
+        policy = search.get_policy(board, n_playouts=999)
+        # root children are visited once per playout of every thread
+        assert sum(policy.visits) == 999
+
+    moves1 = play_seeded_game(MctsSearch(50, 1.0, 3, seed=1, threads=3))
+    moves2 = play_seeded_game(MctsSearch(50, 1.0, 3, seed=1, threads=3))
+    assert moves1 == moves2
+
+
+def test_tree_search_threads_timeout():
+    board = Board()
+    search = MctsSearch(10**9, 1.0, 3, threads=4)
+    start = time.time()
+    result = search.search(board, timeout_ms=100)
+    assert time.time() - start < 1.0
+    assert result.timed_out
+    assert result.best_move in board.get_legal_moves_vec()
+
+
+def test_thunder_threads_py_evaluator():
+    board = Board()
+    evaluator = BatchWinrateEvaluator()
+    search = ThunderSearch(evaluator, 200, 0.1, seed=0, batch_size=4, threads=2)
+    assert search.search(board).nodes == 200
+    assert max(evaluator.batch_sizes) <= 4
+    search = ThunderSearch(ConstWinrateEvaluator(), 200, 0.1, threads=2)
+    assert search.get_move(board) in board.get_legal_moves_vec()
+
+
+def test_mcts_rollout_policies():
+    board = Board()
+    policies = [
+        RolloutPolicy.random(),
+        RolloutPolicy.epsilon_greedy(PieceEvaluator()),
+        RolloutPolicy.corner_first(),
+        RolloutPolicy.corner_first().with_cutoff(10, WinrateEvaluator()),
+    ]
+    for policy in policies:
+        search = MctsSearch(100, 1.0, 3, seed=0, rollout_policy=policy)
+        result = search.search(board)
+        assert result.best_move in board.get_legal_moves_vec()
+        assert result.nodes == 100
+        assert 0.0 <= result.score <= 1.0
+
+
+def test_mcts_rollout_seed():
+    policy = RolloutPolicy.epsilon_greedy(PieceEvaluator(), 0.3)
+    moves1 = play_seeded_game(MctsSearch(30, 1.0, 3, seed=2, rollout_policy=policy))
+    moves2 = play_seeded_game(MctsSearch(30, 1.0, 3, seed=2, rollout_policy=policy))
+    assert moves1 == moves2
+
+
+def test_mcts_rollout_corner_first():
+    board = Board()
+    squares = ["-"] * 64
+    squares[2] = squares[18] = "X"
+    squares[1] = squares[9] = "O"
+    # the corner at 0 captures every white piece, 16 does not
+    board.set_board_str("".join(squares), Turn.BLACK)
+    assert board.get_legal_moves_vec() == [0, 16]
+    search = MctsSearch(200, 1.0, 3, seed=0, rollout_policy=RolloutPolicy.corner_first())
+    assert search.get_move(board) == 0
+
+
+def test_mcts_rollout_cutoff():
+    board = Board()
+    policy = RolloutPolicy.random().with_cutoff(0, ConstWinrateEvaluator())
+    search = MctsSearch(100, 1.0, 3, rollout_policy=policy)
+    assert search.get_search_score(board) == pytest.approx(0.5)
+
+
+def test_mcts_rollout_errors():
+    board = Board()
+    with pytest.raises(ValueError, match="epsilon"):
+        RolloutPolicy.epsilon_greedy(PieceEvaluator(), 1.5)
+    with pytest.raises(ValueError, match="epsilon"):
+        RolloutPolicy.epsilon_greedy(PieceEvaluator(), -0.1)
+
+    policy = RolloutPolicy.epsilon_greedy(FailingEvaluator(), 0.0)
+    with pytest.raises(ZeroDivisionError, match="evaluator bug"):
+        MctsSearch(50, 1.0, 3, rollout_policy=policy).search(board)
+
+    policy = RolloutPolicy.random().with_cutoff(5, WrongTypeWinrateEvaluator())
+    with pytest.raises(TypeError):
+        MctsSearch(50, 1.0, 3, rollout_policy=policy).search(board)